@@ -0,0 +1,75 @@
+//! Introspection of the cache of opened tiles.
+
+use crate::Tiles;
+use std::time::Instant;
+
+/// **CacheStats** describe the cache of opened tiles of a [`Tiles`] object.
+///
+/// They are obtained with [`Tiles::cache_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Names of the opened tiles with their last access, from the most recently used.
+    pub tiles: Vec<(String, Instant)>,
+    /// Number of tile accesses served by an opened tile.
+    pub hits: u64,
+    /// Number of tile accesses which had to search the directories, whether the tile was found
+    /// or not.
+    pub misses: u64,
+    /// Number of opened tiles dropped from the cache.
+    pub evictions: u64,
+}
+
+/// Counters of the cache accesses, since the creation of a [`Tiles`] object.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CacheCounters {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) evictions: u64,
+}
+
+impl Tiles {
+    /// Returns the opened tiles with their last access, and the counters of the cache since the
+    /// creation of this object.
+    ///
+    /// Every tile access (a geoposition lookup, a region row, ...) is counted and timestamped.
+    /// Opened tiles are only dropped by [`Tiles::refresh`], which counts them as evictions. A
+    /// high number of misses compared to the hits points to lookups that keep searching for
+    /// missing tiles.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// tiles.elevation(49.1, -1.6)?;
+    /// let stats = tiles.cache_stats();
+    /// for (name, last_access) in &stats.tiles {
+    ///     println!("{}: used {:?} ago", name, last_access.elapsed());
+    /// }
+    /// println!("{} hits, {} misses", stats.hits, stats.misses);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn cache_stats(&self) -> CacheStats {
+        let mut tiles: Vec<(String, Instant)> = self
+            .handles
+            .borrow()
+            .iter()
+            .map(|(name, handle)| (name.clone(), handle.last_access))
+            .collect();
+        tiles.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let counters = self.counters.get();
+        CacheStats {
+            tiles,
+            hits: counters.hits,
+            misses: counters.misses,
+            evictions: counters.evictions,
+        }
+    }
+
+    /// Updates the counters of the cache.
+    pub(crate) fn count(&self, update: impl FnOnce(&mut CacheCounters)) {
+        let mut counters = self.counters.get();
+        update(&mut counters);
+        self.counters.set(counters);
+    }
+}
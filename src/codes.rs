@@ -0,0 +1,163 @@
+//! Elevation lookups from geohashes and plus codes (Open Location Codes).
+//!
+//! Those strings encode a cell rather than a point: lookups read the elevation at the center of
+//! the cell. Short codes encode large cells (a 5 characters geohash is about 5 km wide, a plus
+//! code padded to 4 digits is 1 degree wide) so the elevation at their center may be far from
+//! the point the code was made for.
+
+use crate::{SrtmError, Tiles};
+use anyhow::Result;
+
+const GEOHASH_ALPHABET: &str = "0123456789bcdefghjkmnpqrstuvwxyz";
+const PLUS_CODE_ALPHABET: &str = "23456789CFGHJMPQRVWX";
+
+impl Tiles {
+    /// Returns the elevation at the center of a geohash cell.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the geohash is empty or holds an invalid character.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let altitude = tiles.elevation_geohash("gbwrv4")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_geohash(&self, gh: &str) -> Result<i16> {
+        let (lat, lng) = decode_geohash(gh)?;
+        self.elevation(lat as f32, lng as f32)
+    }
+
+    /// Returns the elevation at the center of a plus code (Open Location Code) cell.
+    ///
+    /// Only full codes are supported, like "8CXX5J4C+CR" or "8CXX0000+": short codes need a
+    /// reference location to be recovered.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the code is not a valid full plus code.
+    pub fn elevation_plus_code(&self, code: &str) -> Result<i16> {
+        let (lat, lng) = decode_plus_code(code)?;
+        self.elevation(lat as f32, lng as f32)
+    }
+}
+
+/// Returns the center (lat, lng) of a geohash cell.
+fn decode_geohash(gh: &str) -> Result<(f64, f64)> {
+    let invalid = || SrtmError::InvalidArgument(format!("invalid geohash {:?}", gh));
+    if gh.is_empty() {
+        return Err(invalid().into());
+    }
+    let (mut lat, mut lng) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut even = true;
+    for c in gh.chars() {
+        let value = GEOHASH_ALPHABET
+            .find(c.to_ascii_lowercase())
+            .ok_or_else(invalid)?;
+        for bit in (0..5).rev() {
+            // bits alternate between longitude and latitude, starting with longitude
+            let range: &mut (f64, f64) = if even { &mut lng } else { &mut lat };
+            let middle = (range.0 + range.1) / 2.0;
+            if value >> bit & 1 == 1 {
+                range.0 = middle;
+            } else {
+                range.1 = middle;
+            }
+            even = !even;
+        }
+    }
+    Ok(((lat.0 + lat.1) / 2.0, (lng.0 + lng.1) / 2.0))
+}
+
+/// Returns the center (lat, lng) of a full plus code cell.
+fn decode_plus_code(code: &str) -> Result<(f64, f64)> {
+    let invalid = || SrtmError::InvalidArgument(format!("invalid full plus code {:?}", code));
+    let code = code.to_ascii_uppercase();
+    let (pairs, grid) = code.split_once('+').ok_or_else(invalid)?;
+    let digits = pairs.trim_end_matches('0');
+    let padded = digits.len() < pairs.len();
+    if pairs.len() != 8 || digits.len() % 2 == 1 || (padded && !grid.is_empty()) {
+        return Err(invalid().into());
+    }
+    if grid.len() == 1 || digits.is_empty() {
+        return Err(invalid().into());
+    }
+
+    let value = |c: char| PLUS_CODE_ALPHABET.find(c).ok_or_else(invalid);
+    let (mut lat, mut lng) = (-90.0, -180.0);
+    let mut size = (20.0, 20.0);
+    for (i, pair) in digits
+        .chars()
+        .chain(grid.chars().take(2))
+        .collect::<Vec<_>>()
+        .chunks(2)
+        .enumerate()
+    {
+        if i > 0 {
+            size = (size.0 / 20.0, size.1 / 20.0);
+        }
+        lat += value(pair[0])? as f64 * size.0;
+        lng += value(pair[1])? as f64 * size.1;
+    }
+    if lat >= 90.0 || lng >= 180.0 {
+        return Err(invalid().into());
+    }
+    // digits after the 10th one refine a 5 rows × 4 columns grid
+    for c in grid.chars().skip(2) {
+        let value = value(c)?;
+        size = (size.0 / 5.0, size.1 / 4.0);
+        lat += (value / 4) as f64 * size.0;
+        lng += (value % 4) as f64 * size.1;
+    }
+    Ok((lat + size.0 / 2.0, lng + size.1 / 2.0))
+}
+
+/// Validate the geohash decoding against reference cells
+#[test]
+fn it_decodes_geohashes() {
+    let check = |gh, (lat, lng): (f64, f64), precision: f64| {
+        let result = decode_geohash(gh).unwrap();
+        assert!(
+            (result.0 - lat).abs() < precision && (result.1 - lng).abs() < precision,
+            "failed for {:?}: {:?}",
+            gh,
+            result
+        );
+    };
+
+    check("ezs42", (42.605, -5.603), 1e-3);
+    check("u4pruydqqvj", (57.64911, 10.40744), 1e-5);
+    check("EZS42", (42.605, -5.603), 1e-3);
+    check("s", (22.5, 22.5), 1e-9);
+    assert!(decode_geohash("").is_err());
+    assert!(decode_geohash("ezs4a").is_err());
+}
+
+/// Validate the plus code decoding against reference cells
+#[test]
+fn it_decodes_plus_codes() {
+    let check = |code, (lat, lng): (f64, f64)| {
+        let result = decode_plus_code(code).unwrap();
+        assert!(
+            (result.0 - lat).abs() < 1e-9 && (result.1 - lng).abs() < 1e-9,
+            "failed for {:?}: {:?}",
+            code,
+            result
+        );
+    };
+
+    check("CFX30000+", (89.5, 1.5));
+    check("8FVC0000+", (47.5, 8.5));
+    check("8FVC9G00+", (47.35 + 0.025, 8.5 + 0.025));
+    check("8FVC9G8F+", (47.365 + 0.00125, 8.5225 + 0.00125));
+    check("8FVC9G8F+6X", (47.3655625, 8.5249375));
+    check("8FVC9G8F+6XQ", (47.3655875, 8.524984375));
+    assert!(decode_plus_code("9G8F+6X").is_err());
+    assert!(decode_plus_code("8FVC00+").is_err());
+    assert!(decode_plus_code("8FVC0000+6X").is_err());
+    assert!(decode_plus_code("8FVC9G8F+6").is_err());
+    assert!(decode_plus_code("8FVC9G8F+6A").is_err());
+}
@@ -0,0 +1,138 @@
+//! Validated latitude and longitude types.
+
+use crate::{SrtmError, Tiles};
+use anyhow::Result;
+
+/// A latitude, in degrees, within [-90, 90].
+///
+/// Using this type rather than a raw `f32` prevents swapping latitudes and longitudes.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::Latitude;
+/// assert_eq!(Latitude::new(49.1).unwrap().get(), 49.1);
+/// assert!(Latitude::new(-1.6).is_ok());
+/// assert!(Latitude::new(91.0).is_err());
+/// ```
+#[derive(PartialEq, PartialOrd, Clone, Copy, Debug)]
+pub struct Latitude(f32);
+
+/// A longitude, in degrees, within [-180, 180].
+///
+/// Using this type rather than a raw `f32` prevents swapping latitudes and longitudes.
+#[derive(PartialEq, PartialOrd, Clone, Copy, Debug)]
+pub struct Longitude(f32);
+
+impl Latitude {
+    /// Returns the latitude, if within [-90, 90].
+    ///
+    /// # Error
+    ///
+    /// * `SrtmError::OutOfRange` if the value is out of range or not finite.
+    pub fn new(degrees: f32) -> Result<Self, SrtmError> {
+        check_range("latitude", degrees, 90.0).map(Self)
+    }
+
+    /// Returns the latitude in degrees.
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
+impl Longitude {
+    /// Returns the longitude, if within [-180, 180].
+    ///
+    /// # Error
+    ///
+    /// * `SrtmError::OutOfRange` if the value is out of range or not finite.
+    pub fn new(degrees: f32) -> Result<Self, SrtmError> {
+        check_range("longitude", degrees, 180.0).map(Self)
+    }
+
+    /// Returns the longitude in degrees.
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
+impl TryFrom<f32> for Latitude {
+    type Error = SrtmError;
+
+    fn try_from(degrees: f32) -> Result<Self, Self::Error> {
+        Self::new(degrees)
+    }
+}
+
+impl TryFrom<f32> for Longitude {
+    type Error = SrtmError;
+
+    fn try_from(degrees: f32) -> Result<Self, Self::Error> {
+        Self::new(degrees)
+    }
+}
+
+/// Returns the value if within [-max, max].
+pub(crate) fn check_range(name: &'static str, value: f32, max: f32) -> Result<f32, SrtmError> {
+    if (-max..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(SrtmError::OutOfRange { name, value })
+    }
+}
+
+impl Tiles {
+    /// Returns the elevation (height) from validated latitude and longitude.
+    ///
+    /// This is [`Tiles::elevation`], with arguments which cannot be swapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Latitude, Longitude, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let altitude = tiles.elevation_checked(Latitude::new(49.1)?, Longitude::new(-1.6)?)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_checked(&self, lat: Latitude, lng: Longitude) -> Result<i16> {
+        self.elevation(lat.get(), lng.get())
+    }
+
+    /// Returns the elevation (height) from latitude and longitude in radians.
+    ///
+    /// This is [`Tiles::elevation`], with the latitude in [-π/2, π/2] and the longitude in
+    /// [-π, π]. The angles are converted to degrees, then validated as [`Latitude`] and
+    /// [`Longitude`].
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::OutOfRange` if an angle is out of range or not finite.
+    /// * The errors of [`Tiles::elevation`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let altitude = tiles.elevation_rad(0.857, -0.028)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_rad(&self, lat_rad: f64, lng_rad: f64) -> Result<i16> {
+        let lat = Latitude::new(lat_rad.to_degrees() as f32)?;
+        let lng = Longitude::new(lng_rad.to_degrees() as f32)?;
+        self.elevation_checked(lat, lng)
+    }
+}
+
+/// Validate the range checks of coordinates
+#[test]
+fn it_validates_coordinates_ranges() {
+    assert!(Latitude::new(90.0).is_ok());
+    assert!(Latitude::new(-90.0).is_ok());
+    assert!(Latitude::new(90.1).is_err());
+    assert!(Latitude::new(f32::NAN).is_err());
+    assert!(Longitude::new(180.0).is_ok());
+    assert!(Longitude::new(-180.1).is_err());
+    assert!(Longitude::new(f32::INFINITY).is_err());
+    assert_eq!(Longitude::try_from(-1.6).unwrap().get(), -1.6);
+}
@@ -0,0 +1,120 @@
+//! Diagnostics of the sample selection.
+
+use crate::{lng_offset, origin_name, read_sample, srtm_file_coord, Resolution, Tiles};
+use anyhow::Result;
+use std::{fmt, sync::Arc};
+
+/// A **Snapped** sample is the sample selected for a geoposition with one rounding convention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapped {
+    /// Name of the tile the sample is read from.
+    pub tile: String,
+    /// Pixel (x, y) of the sample, from the north-west corner of the tile.
+    pub pixel: (u32, u32),
+    /// Height of the sample.
+    pub height: i16,
+}
+
+/// An **ElevationDebug** compares the samples selected for a geoposition by rounding its pixel
+/// coordinates to the nearest sample, as [`Tiles::elevation`] does, and by flooring them, as
+/// libraries selecting the sample south-west of the geoposition do.
+///
+/// It is obtained with [`Tiles::elevation_debug`]; print it with `{:#?}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevationDebug {
+    /// Latitude of the geoposition.
+    pub lat: f32,
+    /// Longitude of the geoposition.
+    pub lng: f32,
+    /// Resolution of the tile.
+    pub resolution: Resolution,
+    /// Pixel coordinates (x, y) of the geoposition before snapping, from the north-west corner
+    /// of the tile (y increasing southward).
+    pub pixel: (f32, f32),
+    /// Sample selected by rounding the pixel coordinates.
+    pub round: Snapped,
+    /// Sample selected by flooring the pixel coordinates: the south-west corner of the cell
+    /// containing the geoposition.
+    pub floor: Snapped,
+}
+
+/// An **AuditRecord** describes a successful lookup of [`Tiles::elevation`]: the geoposition,
+/// and the sample returned for it.
+///
+/// Records are passed to the sink set with [`crate::TilesBuilder::audit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    /// Latitude of the geoposition.
+    pub lat: f32,
+    /// Longitude of the geoposition.
+    pub lng: f32,
+    /// Resolution of the tile.
+    pub resolution: Resolution,
+    /// Sample returned for the geoposition.
+    pub sample: Snapped,
+}
+
+/// The audit sink of a [`Tiles`] configuration, shared by its clones.
+#[derive(Clone)]
+pub(crate) struct Audit(pub(crate) Arc<dyn Fn(&AuditRecord) + Send + Sync>);
+
+impl fmt::Debug for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Audit")
+    }
+}
+
+impl ElevationDebug {
+    /// Returns true if both conventions select the same sample.
+    pub fn agrees(&self) -> bool {
+        self.round.tile == self.floor.tile && self.round.pixel == self.floor.pixel
+    }
+}
+
+impl Tiles {
+    /// Returns the samples selected for a geoposition by the round and floor conventions.
+    ///
+    /// Both are read from the tile containing the geoposition. They diverge as soon as the
+    /// geoposition is past the middle of its cell, e.g. at lat 49.9996 in a SRTM3 tile (pixel
+    /// row 0.48 from the north): the rounded sample is the northern row, the floored one is the
+    /// row below.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let debug = tiles.elevation_debug(49.9996, -1.6)?;
+    /// if !debug.agrees() {
+    ///     println!("{:#?}", debug);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_debug(&self, lat: f32, lng: f32) -> Result<ElevationDebug> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let tile = origin_name(origin);
+        self.with_tile(origin, |file, resolution| {
+            let (n, m) = (resolution.width() - 1, resolution.height() - 1);
+            let px = lng_offset(lng, origin.1) * n as f32;
+            let py = (lat - origin.0 as f32) * m as f32;
+            let round = srtm_file_coord(lat, lng, origin, resolution);
+            let floor = ((px.floor() as u32).min(n), m - (py.floor() as u32).min(m));
+            Ok(ElevationDebug {
+                lat,
+                lng,
+                resolution,
+                pixel: (px, m as f32 - py),
+                round: Snapped {
+                    tile: tile.clone(),
+                    pixel: round,
+                    height: read_sample(file, resolution, round.0, round.1)?,
+                },
+                floor: Snapped {
+                    tile: tile.clone(),
+                    pixel: floor,
+                    height: read_sample(file, resolution, floor.0, floor.1)?,
+                },
+            })
+        })
+    }
+}
@@ -0,0 +1,275 @@
+//! Compact encodings of region samples.
+
+use crate::{Grid, Resolution, SrtmError, Tiles, VOID};
+use anyhow::Result;
+use byteorder::{BigEndian, ByteOrder};
+
+/// Band index of the voids in [`Tiles::band_grid`].
+pub const VOID_BAND: u8 = 255;
+
+/// First bytes of the regions encoded by [`Tiles::encode_region`].
+const REGION_MAGIC: &[u8; 4] = b"SRD1";
+
+/// Size, in bytes, of the header of the regions encoded by [`Tiles::encode_region`].
+const REGION_HEADER_LEN: usize = 21;
+
+impl Tiles {
+    /// Returns the samples of a region as runs of equal consecutive samples `(height, count)`.
+    ///
+    /// Samples are taken row-major, as in [`Tiles::grid`], and runs continue from a row to the
+    /// next one. Flat or water areas repeat the same height a lot, so that runs are much smaller
+    /// than the raw grid. The grid dimensions are those of [`Tiles::rows`] for the same corners;
+    /// use [`expand_rle`] to get the samples back.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{expand_rle, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let runs = tiles.grid_rle((49.5, -1.5), (49.0, -1.0))?;
+    /// let samples = expand_rle(&runs);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn grid_rle(&self, from: (f32, f32), to: (f32, f32)) -> Result<Vec<(i16, u32)>> {
+        let mut runs: Vec<(i16, u32)> = Vec::new();
+        for row in self.rows(from, to)? {
+            for height in row? {
+                match runs.last_mut() {
+                    Some((last, count)) if *last == height => *count += 1,
+                    _ => runs.push((height, 1)),
+                }
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Returns the samples of a region classified into elevation bands, as
+    /// `(width, height, bands)`.
+    ///
+    /// The band of a sample is the number of `breaks` lower than or equal to its height: 0
+    /// below the first break, 1 from the first break to the second one, and so on. Voids are
+    /// mapped to [`VOID_BAND`]. Bands are row-major, as in [`Tiles::grid`], and read from
+    /// [`Tiles::rows`] for the same corners.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `breaks` is empty, not sorted, or holds more than 254
+    ///   breaks.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // water, lowlands, hills, mountains
+    /// let (width, height, bands) = tiles.band_grid((49.5, -1.5), (49.0, -1.0), &[1, 200, 1000])?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn band_grid(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        breaks: &[i16],
+    ) -> Result<(u32, u32, Vec<u8>)> {
+        if breaks.is_empty() || breaks.len() >= VOID_BAND as usize {
+            return Err(SrtmError::InvalidArgument(format!(
+                "between 1 and {} breaks expected, not {}",
+                VOID_BAND - 1,
+                breaks.len()
+            ))
+            .into());
+        }
+        if breaks.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(
+                SrtmError::InvalidArgument(format!("breaks {:?} are not sorted", breaks)).into(),
+            );
+        }
+
+        let rows = self.rows(from, to)?;
+        let (width, height) = (rows.width(), rows.height());
+        let mut bands = Vec::with_capacity(width as usize * height as usize);
+        for row in rows {
+            bands.extend(row?.into_iter().map(|sample| {
+                if self.is_void_height(sample) {
+                    VOID_BAND
+                } else {
+                    breaks.partition_point(|&b| b <= sample) as u8
+                }
+            }));
+        }
+        Ok((width, height, bands))
+    }
+
+    /// Returns the samples of a region, as in [`Tiles::grid`], delta-encoded in a compact
+    /// byte buffer. Use [`decode_region`] to get the [`Grid`] back.
+    ///
+    /// Neighboring samples differ little, so that their differences mostly fit in a single
+    /// byte (from -63 to 63 m): the buffer is then about half the size of the raw samples.
+    ///
+    /// # Format
+    ///
+    /// Multi-byte values are big-endian. The buffer starts with a 21 bytes header:
+    ///
+    /// | offset | size | content                                              |
+    /// |--------|------|------------------------------------------------------|
+    /// | 0      | 4    | `SRD1`, in ASCII                                     |
+    /// | 4      | 1    | resolution, in arc-seconds: 1 (SRTM1) or 3 (SRTM3)   |
+    /// | 5      | 4    | latitude of the north-west sample, as an IEEE 754 f32  |
+    /// | 9      | 4    | longitude of the north-west sample, as an IEEE 754 f32 |
+    /// | 13     | 4    | width, as an u32                                     |
+    /// | 17     | 4    | height, as an u32                                    |
+    ///
+    /// followed by `width * height` codes for the samples, row-major (see [`Grid`]). Each code
+    /// is an unsigned LEB128 varint (7 bits per byte, least significant first, the high bit set
+    /// on all bytes but the last one):
+    ///
+    /// * 0 is a void (see [`crate::TilesBuilder::void_value`]);
+    /// * `zigzag(height - previous) + 1` otherwise, where `previous` is the height of the last
+    ///   sample which is not a void (0 for the first one), and
+    ///   `zigzag(d) = 2 * d` for `d >= 0`, `-2 * d - 1` for `d < 0`.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the region is not made of SRTM1 or SRTM3 tiles.
+    /// * The errors of [`Tiles::rows`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{decode_region, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let bytes = tiles.encode_region((49.5, -1.5), (49.0, -1.0))?;
+    /// // ... send the bytes to the client
+    /// let grid = decode_region(&bytes)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn encode_region(&self, from: (f32, f32), to: (f32, f32)) -> Result<Vec<u8>> {
+        let rows = self.rows(from, to)?;
+        let mut bytes = Vec::with_capacity(REGION_HEADER_LEN + rows.width() as usize * 2);
+        bytes.extend_from_slice(REGION_MAGIC);
+        bytes.push(match rows.resolution() {
+            Resolution::SRTM1 => 1,
+            Resolution::SRTM3 => 3,
+            Resolution::Dimensions { width, height } => {
+                return Err(SrtmError::InvalidArgument(format!(
+                    "tiles of {}×{} samples cannot be encoded",
+                    width, height
+                ))
+                .into())
+            }
+        });
+        let (lat, lng) = rows.north_west();
+        let mut header = [0; 16];
+        BigEndian::write_f32(&mut header[0..4], lat);
+        BigEndian::write_f32(&mut header[4..8], lng);
+        BigEndian::write_u32(&mut header[8..12], rows.width());
+        BigEndian::write_u32(&mut header[12..16], rows.height());
+        bytes.extend_from_slice(&header);
+
+        let mut previous = 0i32;
+        for row in rows {
+            for height in row? {
+                let code = if self.is_void_height(height) {
+                    0
+                } else {
+                    let delta = height as i32 - previous;
+                    previous = height as i32;
+                    ((delta << 1) ^ (delta >> 31)) as u32 + 1
+                };
+                write_varint(&mut bytes, code);
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// Appends an unsigned LEB128 varint.
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Decodes a region encoded by [`Tiles::encode_region`].
+///
+/// Voids are decoded as [`VOID`], whatever the void value of the encoding [`Tiles`].
+///
+/// # Errors
+///
+/// * `SrtmError::InvalidArgument` if the bytes do not follow the format of
+///   [`Tiles::encode_region`], or are truncated.
+pub fn decode_region(bytes: &[u8]) -> Result<Grid> {
+    let invalid = |reason: &str| SrtmError::InvalidArgument(format!("invalid region: {}", reason));
+    if bytes.len() < REGION_HEADER_LEN || &bytes[0..4] != REGION_MAGIC {
+        return Err(invalid("no header").into());
+    }
+    let resolution = match bytes[4] {
+        1 => Resolution::SRTM1,
+        3 => Resolution::SRTM3,
+        _ => return Err(invalid("unknown resolution").into()),
+    };
+    let north_west = (
+        BigEndian::read_f32(&bytes[5..9]),
+        BigEndian::read_f32(&bytes[9..13]),
+    );
+    let (width, height) = (
+        BigEndian::read_u32(&bytes[13..17]),
+        BigEndian::read_u32(&bytes[17..21]),
+    );
+
+    let len = width as usize * height as usize;
+    let mut data = Vec::with_capacity(len.min(bytes.len()));
+    let (mut value, mut shift, mut previous) = (0u32, 0, 0i32);
+    for &byte in &bytes[REGION_HEADER_LEN..] {
+        if data.len() == len {
+            return Err(invalid("trailing bytes").into());
+        }
+        if shift > 28 {
+            return Err(invalid("overlong code").into());
+        }
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 != 0 {
+            shift += 7;
+            continue;
+        }
+        if value == 0 {
+            data.push(VOID);
+        } else {
+            let code = value - 1;
+            previous = previous
+                .checked_add((code >> 1) as i32 ^ -((code & 1) as i32))
+                .ok_or_else(|| invalid("height out of range"))?;
+            data.push(i16::try_from(previous).map_err(|_| invalid("height out of range"))?);
+        }
+        (value, shift) = (0, 0);
+    }
+    if data.len() != len || shift != 0 {
+        return Err(invalid("truncated samples").into());
+    }
+    Ok(Grid {
+        width,
+        height,
+        resolution,
+        north_west,
+        data,
+    })
+}
+
+/// Expands runs of equal samples `(height, count)`, as returned by [`Tiles::grid_rle`].
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::expand_rle;
+/// assert_eq!(expand_rle(&[(0, 3), (12, 1), (0, 2)]), vec![0, 0, 0, 12, 0, 0]);
+/// ```
+pub fn expand_rle(runs: &[(i16, u32)]) -> Vec<i16> {
+    let len = runs.iter().map(|&(_, count)| count as usize).sum();
+    let mut samples = Vec::with_capacity(len);
+    for &(height, count) in runs {
+        samples.resize(samples.len() + count as usize, height);
+    }
+    samples
+}
@@ -0,0 +1,132 @@
+//! Tile files, standalone or stored in an archive.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// A tile stored in an archive, as the byte range of its entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ArchivedTile {
+    pub(crate) path: PathBuf,
+    pub(crate) start: u64,
+    pub(crate) len: u64,
+}
+
+/// An opened tile: a whole file, or the byte range of an archive entry.
+///
+/// Seeking is relative to the start of the tile. Reads are not bounded to the tile length: the
+/// readers only access samples inside the tile.
+#[derive(Debug)]
+pub(crate) struct TileFile {
+    file: File,
+    start: u64,
+    len: u64,
+    /// Bytes read ahead by [`TileFile::read_ahead`], from the offset `ahead_start`.
+    ahead: Vec<u8>,
+    ahead_start: u64,
+}
+
+impl TileFile {
+    /// Opens a whole file as a tile.
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file,
+            start: 0,
+            len,
+            ahead: Vec::new(),
+            ahead_start: 0,
+        })
+    }
+
+    /// Opens the entry of an archive as a tile.
+    pub(crate) fn open_archived(tile: &ArchivedTile) -> io::Result<Self> {
+        Ok(Self {
+            file: File::open(&tile.path)?,
+            start: tile.start,
+            len: tile.len,
+            ahead: Vec::new(),
+            ahead_start: 0,
+        })
+    }
+
+    /// Returns the size, in bytes, of the tile.
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Reads exactly `buf.len()` bytes at `offset` in the tile, through a read-ahead buffer of
+    /// `capacity` bytes.
+    ///
+    /// A read outside of the buffer refills it with the `capacity` bytes from `offset` (up to
+    /// the end of the tile), so that the following sequential reads are served from memory.
+    /// Reads larger than the buffer bypass it.
+    pub(crate) fn read_ahead(
+        &mut self,
+        offset: u64,
+        buf: &mut [u8],
+        capacity: usize,
+    ) -> io::Result<()> {
+        let end = offset + buf.len() as u64;
+        let buffered = self.ahead_start..=self.ahead_start + self.ahead.len() as u64;
+        if !(buffered.contains(&offset) && buffered.contains(&end)) {
+            if buf.len() >= capacity {
+                self.seek(SeekFrom::Start(offset))?;
+                return self.read_exact(buf);
+            }
+            let len = (capacity as u64)
+                .min(self.len.saturating_sub(offset))
+                .max(buf.len() as u64);
+            self.ahead.resize(len as usize, 0);
+            (&self.file).seek(SeekFrom::Start(self.start + offset))?;
+            if let Err(e) = (&self.file).read_exact(&mut self.ahead) {
+                self.ahead.clear();
+                return Err(e);
+            }
+            self.ahead_start = offset;
+        }
+        let from = (offset - self.ahead_start) as usize;
+        buf.copy_from_slice(&self.ahead[from..from + buf.len()]);
+        Ok(())
+    }
+}
+
+impl Read for &TileFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.file).read(buf)
+    }
+}
+
+impl Seek for &TileFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(self.start + offset),
+            SeekFrom::End(offset) => (self.start + self.len).checked_add_signed(offset),
+            SeekFrom::Current(offset) => (&self.file).stream_position()?.checked_add_signed(offset),
+        };
+        match target {
+            Some(target) if target >= self.start => {
+                Ok((&self.file).seek(SeekFrom::Start(target))? - self.start)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the tile",
+            )),
+        }
+    }
+}
+
+impl Read for TileFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl Seek for TileFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        (&*self).seek(pos)
+    }
+}
@@ -0,0 +1,148 @@
+//! Geodesy helpers on a spherical earth.
+
+/// Mean earth radius, in meters.
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Returns the great-circle distance, in meters, between two geopositions (lat, lng).
+///
+/// This uses the haversine formula on a sphere of radius 6371008.8 m (the mean earth radius):
+/// errors against the WGS84 ellipsoid stay below 0.5%.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::haversine_m;
+/// let degree = haversine_m((0.0, 0.0), (1.0, 0.0));
+/// assert!((degree - 111_195.0).abs() < 1.0);
+/// ```
+pub fn haversine_m(from: (f32, f32), to: (f32, f32)) -> f32 {
+    (angular_distance(from, to) * EARTH_RADIUS_M) as f32
+}
+
+/// Returns the initial bearing, in degrees, of the great circle path between two geopositions
+/// (lat, lng).
+///
+/// The bearing is measured clockwise from the north, in [0, 360): 0 is north, 90 is east. It
+/// is the direction at `from`, which changes along the path, except along the meridians and
+/// the equator. At a pole, where every direction is south (or north), the bearing is 180
+/// from the north pole and 0 from the south pole, whatever `to`. Identical geopositions have
+/// a bearing of 0.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::bearing_deg;
+/// assert!((bearing_deg((49.0, -2.0), (50.0, -2.0)) - 0.0).abs() < 1e-3);
+/// assert!((bearing_deg((0.0, 0.0), (0.0, -1.0)) - 270.0).abs() < 1e-3);
+/// // the great circle from Paris to New York heads north-west
+/// let bearing = bearing_deg((48.857, 2.352), (40.713, -74.006));
+/// assert!((bearing - 291.8).abs() < 0.1);
+/// ```
+pub fn bearing_deg(from: (f32, f32), to: (f32, f32)) -> f32 {
+    if from.0 >= 90.0 {
+        return 180.0;
+    }
+    if from.0 <= -90.0 {
+        return 0.0;
+    }
+    let (lat1, lng1) = radians(from);
+    let (lat2, lng2) = radians(to);
+    let y = (lng2 - lng1).sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * (lng2 - lng1).cos();
+    // bearings just below 360 are rounded to 360 in f32
+    (y.atan2(x).to_degrees().rem_euclid(360.0) as f32).rem_euclid(360.0)
+}
+
+/// Returns the great-circle angular distance, in radians, between two geopositions.
+pub(crate) fn angular_distance(from: (f32, f32), to: (f32, f32)) -> f64 {
+    let (lat1, lng1) = radians(from);
+    let (lat2, lng2) = radians(to);
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lng2 - lng1) / 2.0).sin().powi(2);
+    2.0 * a.sqrt().min(1.0).asin()
+}
+
+/// Returns the geoposition at `fraction` of the great circle path between two geopositions.
+pub(crate) fn intermediate(from: (f32, f32), to: (f32, f32), fraction: f64) -> (f32, f32) {
+    let delta = angular_distance(from, to);
+    if delta < 1e-12 {
+        return from;
+    }
+    let (lat1, lng1) = radians(from);
+    let (lat2, lng2) = radians(to);
+    let a = ((1.0 - fraction) * delta).sin() / delta.sin();
+    let b = (fraction * delta).sin() / delta.sin();
+    let x = a * lat1.cos() * lng1.cos() + b * lat2.cos() * lng2.cos();
+    let y = a * lat1.cos() * lng1.sin() + b * lat2.cos() * lng2.sin();
+    let z = a * lat1.sin() + b * lat2.sin();
+    let lat = z.atan2((x * x + y * y).sqrt());
+    (lat.to_degrees() as f32, y.atan2(x).to_degrees() as f32)
+}
+
+/// Returns the geoposition at `distance_m` meters from `from` along the great circle starting
+/// with the initial bearing `bearing_deg` (in degrees clockwise from the north).
+pub(crate) fn destination(from: (f32, f32), bearing_deg: f64, distance_m: f64) -> (f32, f32) {
+    let (lat1, lng1) = radians(from);
+    let (delta, theta) = (distance_m / EARTH_RADIUS_M, bearing_deg.to_radians());
+    let lat = (lat1.sin() * delta.cos() + lat1.cos() * delta.sin() * theta.cos()).asin();
+    let lng =
+        lng1 + (theta.sin() * delta.sin() * lat1.cos()).atan2(delta.cos() - lat1.sin() * lat.sin());
+    let lng = (lng.to_degrees() + 540.0).rem_euclid(360.0) - 180.0;
+    (lat.to_degrees() as f32, lng as f32)
+}
+
+/// Converts a geoposition to radians, in f64.
+fn radians((lat, lng): (f32, f32)) -> (f64, f64) {
+    ((lat as f64).to_radians(), (lng as f64).to_radians())
+}
+
+/// Validate the great circle intermediate points
+#[test]
+fn it_computes_great_circle_intermediate_points() {
+    let (from, to) = ((49.0, -2.0), (50.0, 0.0));
+    let middle = intermediate(from, to, 0.5);
+    let (d1, d2) = (haversine_m(from, middle), haversine_m(middle, to));
+    assert!((d1 - d2).abs() < 0.5);
+    assert!((d1 + d2 - haversine_m(from, to)).abs() < 0.5);
+
+    assert_eq!(intermediate(from, to, 0.0), from);
+    let end = intermediate(from, to, 1.0);
+    assert!((end.0 - to.0).abs() < 1e-5 && (end.1 - to.1).abs() < 1e-5);
+
+    // along the equator
+    let quarter = intermediate((0.0, 0.0), (0.0, 40.0), 0.25);
+    assert!(quarter.0.abs() < 1e-5 && (quarter.1 - 10.0).abs() < 1e-4);
+}
+
+/// Validate the destination points along great circles
+#[test]
+fn it_computes_great_circle_destinations() {
+    let from = (49.0, -2.0);
+    let north = destination(from, 0.0, 10_000.0);
+    assert!((north.1 - from.1).abs() < 1e-5);
+    assert!((haversine_m(from, north) - 10_000.0).abs() < 0.5);
+    let east = destination(from, 90.0, 10_000.0);
+    assert!(east.1 > from.1 && (haversine_m(from, east) - 10_000.0).abs() < 0.5);
+
+    // along the equator, across the antimeridian
+    let west = destination((0.0, -179.9), 270.0, 0.2f64.to_radians() * EARTH_RADIUS_M);
+    assert!(west.0.abs() < 1e-5 && (west.1 - 179.9).abs() < 1e-4);
+}
+
+/// Validate the initial bearings against the destination points
+#[test]
+fn it_computes_initial_bearings() {
+    let from = (49.0, -2.0);
+    for bearing in [0.0, 45.0, 90.0, 135.0, 180.0, 250.0, 359.0] {
+        let to = destination(from, bearing, 50_000.0);
+        let result = bearing_deg(from, to) as f64;
+        let error = (result - bearing + 180.0).rem_euclid(360.0) - 180.0;
+        assert!(error.abs() < 1e-3, "failed for {}: {}", bearing, result);
+    }
+
+    // across the antimeridian
+    assert!((bearing_deg((0.0, 179.5), (0.0, -179.5)) - 90.0).abs() < 1e-3);
+    assert_eq!(bearing_deg((90.0, 0.0), (49.0, -2.0)), 180.0);
+    assert_eq!(bearing_deg((-90.0, 0.0), (49.0, -2.0)), 0.0);
+    assert_eq!(bearing_deg(from, from), 0.0);
+}
@@ -0,0 +1,256 @@
+//! Handle on a single opened tile.
+
+use crate::{
+    file::TileFile, hgt_name, lng_offset, name_origin, origin_name, read_sample, srtm_file_coord,
+    tile_path, Resolution, SrtmError, Tiles,
+};
+use anyhow::Result;
+use std::{
+    io::{Read, Seek, SeekFrom},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// A **TileHandle** reads the elevations of a single tile.
+///
+/// It is obtained with [`Tiles::pin_tile`] and keeps the opened file and its resolution, so
+/// that lookups skip the tiles cache: this is the fast path for queries known to fall in a single
+/// tile.
+#[derive(Debug)]
+pub struct TileHandle {
+    name: String,
+    origin: (i32, i32),
+    resolution: Resolution,
+    file: TileFile,
+}
+
+/// A **TileRef** gives access to the raw bytes of a single tile.
+///
+/// It is obtained with [`Tiles::tile_bytes`] and dereferences to the big-endian samples of the
+/// tile, row-major from the north-west corner. The bytes are a copy read once from the tile,
+/// owned by the TileRef: they are not reread after a [`Tiles::refresh`].
+#[derive(Debug)]
+pub struct TileRef {
+    resolution: Resolution,
+    bytes: Vec<u8>,
+}
+
+/// A **TileInfo** describes the tile covering a geoposition, as returned by
+/// [`Tiles::covering_tile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileInfo {
+    /// Name of the tile, e.g. "N49W002.hgt".
+    pub name: String,
+    /// Geoposition (lat, lng) of the south-west corner of the tile.
+    pub sw: (f32, f32),
+    /// Geoposition (lat, lng) of the north-east corner of the tile.
+    pub ne: (f32, f32),
+    /// Resolution of the tile, detected from its size.
+    pub resolution: Resolution,
+    /// Path of the tile file, or of the archive containing the tile.
+    pub path: PathBuf,
+}
+
+impl Tiles {
+    /// Returns the name, bounds, resolution and path of the tile covering the geoposition.
+    ///
+    /// Only the metadata of the file is read, not its samples, and the tile is not opened: the
+    /// information reflects the directories at the time of the call, e.g. to invalidate the
+    /// map tiles rendered from a tile which was just downloaded.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if the tile is not found.
+    /// * `SrtmError::ResolutionError` if the file size is not a tile size.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let tile = tiles.covering_tile(49.1, -1.6)?;
+    /// assert_eq!((tile.sw, tile.ne), ((49.0, -2.0), (50.0, -1.0)));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn covering_tile(&self, lat: f32, lng: f32) -> Result<TileInfo> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let name = origin_name(origin);
+        let (path, len) = match self.archived.get(&name) {
+            Some(tile) => (tile.path.clone(), tile.len),
+            None => self
+                .directories
+                .iter()
+                .filter_map(|directory| tile_path(directory, &name))
+                .find_map(|path| {
+                    let len = path.metadata().ok().filter(|m| m.is_file())?.len();
+                    Some((path, len))
+                })
+                .ok_or_else(|| SrtmError::MissingTile(name.clone()))?,
+        };
+        let (lat0, lng0) = (origin.0 as f32, origin.1 as f32);
+        Ok(TileInfo {
+            sw: (lat0, lng0),
+            ne: (lat0 + 1.0, lng0 + 1.0),
+            resolution: self.config.resolution(len)?,
+            name,
+            path,
+        })
+    }
+
+    /// Returns a copy of the raw bytes of the tile containing the geoposition.
+    ///
+    /// Unlike [`Tiles::load_tile`], samples are not decoded: use [`TileRef::sample`] to decode
+    /// them on the fly.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let tile = tiles.tile_bytes(49.1, -1.6)?;
+    /// let north_west = tile.sample(0, 0);
+    /// let checksum = tile.iter().fold(0u8, |sum, byte| sum ^ byte);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn tile_bytes(&self, lat: f32, lng: f32) -> Result<TileRef> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            let mut bytes = vec![0; resolution.file_size() as usize];
+            file.seek(SeekFrom::Start(0))?;
+            file.read_exact(&mut bytes)?;
+            Ok(TileRef { resolution, bytes })
+        })
+    }
+
+    /// Returns a handle on the tile containing the geoposition.
+    ///
+    /// The handle opens its own file, independent from the cache of this Tiles object.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let tile = tiles.pin_tile(49.1, -1.6)?;
+    /// let altitude = tile.elevation(49.2, -1.7)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn pin_tile(&self, lat: f32, lng: f32) -> Result<TileHandle> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let name = origin_name(origin);
+        let file = self.open_tile(&name)?;
+        let resolution = self.config.resolution(file.len())?;
+        Ok(TileHandle {
+            name,
+            origin,
+            resolution,
+            file,
+        })
+    }
+}
+
+/// Returns the elevation (height) from latitude and longitude, read from the given tile file.
+///
+/// This suits one-off lookups in a known tile: the file is opened for this call only, without
+/// any [`Tiles`] object. The resolution is detected from the file size, and the tile corner
+/// from the file name, which must be a tile name in any case (e.g. `N49W002.hgt`, see
+/// [`crate::tile_name`]).
+///
+/// # Errors
+///
+/// * `SrtmError::InvalidArgument` if the file is not named as a tile.
+/// * `SrtmError::OutsideTile` if the geoposition is not inside the tile, edges included.
+/// * `SrtmError::ResolutionError` if the file size is not a tile size.
+///
+/// # Example
+///
+/// ```no_run
+/// use easy_srtm::elevation_from_file;
+/// let altitude = elevation_from_file("the_folder_path/N49W002.hgt", 49.1, -1.6)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn elevation_from_file<P: AsRef<Path>>(path: P, lat: f32, lng: f32) -> Result<i16> {
+    let path = path.as_ref();
+    let (origin, name) = hgt_name(path)
+        .and_then(|name| Some((name_origin(&name)?, name)))
+        .ok_or_else(|| SrtmError::InvalidArgument(format!("{:?} is not named as a tile", path)))?;
+    let file = TileFile::open(path)?;
+    let resolution = Resolution::try_from(file.len())?;
+    TileHandle {
+        name,
+        origin,
+        resolution,
+        file,
+    }
+    .elevation(lat, lng)
+}
+
+impl TileHandle {
+    /// Returns the name of the tile.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the resolution of the tile.
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Returns true if the geoposition is inside the tile, edges included.
+    pub fn contains(&self, lat: f32, lng: f32) -> bool {
+        let lat0 = self.origin.0 as f32;
+        (lat0..=lat0 + 1.0).contains(&lat) && lng_offset(lng, self.origin.1) <= 1.0
+    }
+
+    /// Returns the elevation (height) from latitude and longitude.
+    ///
+    /// See [`Tiles::elevation`].
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::OutsideTile` if the geoposition is not inside the tile.
+    pub fn elevation(&self, lat: f32, lng: f32) -> Result<i16> {
+        if !self.contains(lat, lng) {
+            return Err(SrtmError::OutsideTile {
+                lat,
+                lng,
+                tile: self.name.clone(),
+            }
+            .into());
+        }
+        let (x, y) = srtm_file_coord(lat, lng, self.origin, self.resolution);
+        read_sample(&mut &self.file, self.resolution, x, y)
+    }
+}
+
+impl TileRef {
+    /// Returns the resolution of the tile.
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Returns the sample at the (x, y) pixel coordinates, from the north-west corner.
+    ///
+    /// # Panics
+    ///
+    /// If x or y is not lower than the tile side.
+    pub fn sample(&self, x: u32, y: u32) -> i16 {
+        let width = self.resolution.width();
+        assert!(
+            x < width && y < self.resolution.height(),
+            "sample ({}, {}) outside the tile",
+            x,
+            y
+        );
+        let index = (x + y * width) as usize * 2;
+        i16::from_be_bytes([self.bytes[index], self.bytes[index + 1]])
+    }
+}
+
+impl Deref for TileRef {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
@@ -11,7 +11,7 @@
 //! The (probably only) use case would be:
 //!
 //! 1. [download](http://dwtkns.com/srtm30m/) SRTM files with
-//! an [account](https://urs.earthdata.nasa.gov/)
+//!    an [account](https://urs.earthdata.nasa.gov/)
 //!
 //! 2. Put tiles files in a folder
 //!
@@ -30,6 +30,17 @@
 //!
 //! ```
 //!
+//! Latitudes and longitudes are easily swapped: prefer the validated [`Latitude`] and
+//! [`Longitude`] types with [`Tiles::elevation_checked`]:
+//!
+//! ```no_run
+//! use easy_srtm::{Latitude, Longitude, Tiles};
+//! let tiles = Tiles::new("the_folder_path");
+//! let (lat, lng) = (Latitude::new(49.1)?, Longitude::new(-1.6)?);
+//! let altitude = tiles.elevation_checked(lat, lng)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
 //! ## SRTM description
 //!
 //! See SRTM [description](https://www.usgs.gov/centers/eros/science/usgs-eros-archive-digital-elevation-shuttle-radar-topography-mission-srtm-non)
@@ -44,36 +55,123 @@
 //! - C-band Wavelength: 5.6 cm
 //!
 
-use anyhow::Result;
-use byteorder::{self, BigEndian, ReadBytesExt};
+use anyhow::{Context, Result};
+use byteorder::{self, BigEndian, ByteOrder, ReadBytesExt};
+use cache::CacheCounters;
+use file::{ArchivedTile, TileFile};
+use stats::Histogram;
 use std::{
-    cell::RefCell,
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
     fs::File,
-    io::{Seek, SeekFrom},
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
+mod cache;
+#[cfg(feature = "geohash")]
+mod codes;
+mod coordinates;
+mod debug;
+mod encoding;
+mod file;
+mod geo;
+mod handle;
+mod memory;
+mod mesh;
+mod order;
+mod points;
+mod profile;
+#[cfg(feature = "proj")]
+mod projection;
+mod region;
+mod smoothing;
+mod stats;
+#[cfg(feature = "tar")]
+mod tar;
+mod terrain;
+
+pub use cache::CacheStats;
+pub use coordinates::{Latitude, Longitude};
+pub use debug::{AuditRecord, ElevationDebug, Snapped};
+pub use encoding::{decode_region, expand_rle, VOID_BAND};
+pub use geo::{bearing_deg, haversine_m};
+pub use handle::{elevation_from_file, TileHandle, TileInfo, TileRef};
+pub use memory::MemoryTiles;
+pub use mesh::{MeshStrip, MeshStrips};
+pub use order::TileOrder;
+pub use points::{Elevations, ResolvedElevations};
+pub use profile::ProfilePoint;
+pub use region::{Grid, Rows};
+pub use smoothing::Kernel;
+pub use stats::Stats;
+pub use terrain::Terrain;
+
 #[derive(Error, Debug)]
 pub enum SrtmError {
     #[error("File size is not STRM(1|3) compatible")]
     ResolutionError,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Region spans tiles of different resolutions")]
+    MixedResolution,
+    #[error("Buffer too small: {needed} samples needed, {len} provided")]
+    BufferTooSmall { needed: usize, len: usize },
+    #[error("Tile {0} is missing")]
+    MissingTile(String),
+    #[error("Tile {0} holds only zeros or voids")]
+    CorruptTile(String),
+    #[error("File holds {len} bytes, at least {needed} expected")]
+    FileTooShort { len: u64, needed: u64 },
+    #[error("No valid sample near ({lat}, {lng})")]
+    NoData { lat: f32, lng: f32 },
+    #[error("Coordinate reference system EPSG:{0} is not supported")]
+    UnsupportedCrs(u32),
+    #[error("The {name} {value} is out of range")]
+    OutOfRange { name: &'static str, value: f32 },
+    #[error("Environment variable {0} is not set")]
+    MissingEnvVar(String),
+    #[error("File {0:?} is not a valid tar archive")]
+    InvalidArchive(PathBuf),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Geoposition ({lat}, {lng}) is outside of tile {tile}")]
+    OutsideTile { lat: f32, lng: f32, tile: String },
+    #[error("The operation was cancelled")]
+    Cancelled,
+    #[error("Reading the tile {0} timed out")]
+    Timeout(String),
 }
 
 const SRTM1_FSIZE: u64 = 3601 * 3601 * 2;
 const SRTM3_FSIZE: u64 = 1201 * 1201 * 2;
 
+/// Height of the samples without data (voids) in SRTM files.
+pub const VOID: i16 = -32768;
+
 /// Tile resolution.
 ///
 /// SRTM files are squares.
 ///
 /// - SRTM1: Spatial Resolution: 1 arc-second for global coverage (~30 meters)
 /// - SRTM3: Spatial Resolution: 3 arc-seconds for global coverage (~90 meters)
+///
+/// Other products in the .hgt format may have tiles of any dimensions, e.g. at high latitudes,
+/// with fewer columns as the longitude spacing widens: see [`TilesBuilder::dimensions`].
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Resolution {
     SRTM1,
     SRTM3,
+    /// Tiles of `width` columns and `height` rows, covering one degree with an overlapped
+    /// column and row like the SRTM ones.
+    Dimensions {
+        width: u32,
+        height: u32,
+    },
 }
 
 impl TryFrom<u64> for Resolution {
@@ -111,38 +209,565 @@ impl TryFrom<u64> for Resolution {
     }
 }
 
+impl TryFrom<&Path> for Resolution {
+    type Error = SrtmError;
+
+    /// Instanciate the resolution from the size of a file.
+    ///
+    /// # Error
+    ///
+    /// * `SrtmError::Io` if the file size cannot be read (e.g. a missing file).
+    /// * `SrtmError::ResolutionError` in case of bad filesize.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Resolution;
+    /// use std::path::Path;
+    /// let resolution = Resolution::try_from(Path::new("the_folder_path/N49W002.hgt"))?;
+    /// # Ok::<(), easy_srtm::SrtmError>(())
+    /// ```
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        Resolution::try_from(std::fs::metadata(path)?.len())
+    }
+}
+
 /// SRTM files are squares.
 /// The side size depends on the subformat:
 /// - 3601 values for one earth arc degree (and an overlapped value) with SRTM1
 /// - 1201 values for one earth arc degree (and an overlapped value) with SRTM3
 impl Resolution {
-    /// Returns the side size of a tile having this resolution.
-    fn side(&self) -> u32 {
-        match &self {
+    /// Returns the number of columns of a tile having this resolution.
+    pub(crate) fn width(&self) -> u32 {
+        match *self {
             Resolution::SRTM1 => 3601,
             Resolution::SRTM3 => 1201,
+            Resolution::Dimensions { width, .. } => width,
+        }
+    }
+
+    /// Returns the number of rows of a tile having this resolution.
+    pub(crate) fn height(&self) -> u32 {
+        match *self {
+            Resolution::Dimensions { height, .. } => height,
+            _ => self.width(),
+        }
+    }
+
+    /// Returns the size, in bytes, of a tile file having this resolution.
+    ///
+    /// This is the inverse of `Resolution::try_from(filesize)`, e.g. to validate downloads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Resolution;
+    /// assert_eq!(Resolution::SRTM1.file_size(), 3601 * 3601 * 2);
+    /// let downloaded_size = Resolution::SRTM3.file_size();
+    /// assert_eq!(Resolution::try_from(downloaded_size).unwrap(), Resolution::SRTM3);
+    /// ```
+    pub fn file_size(&self) -> u64 {
+        match &self {
+            Resolution::SRTM1 => SRTM1_FSIZE,
+            Resolution::SRTM3 => SRTM3_FSIZE,
+            Resolution::Dimensions { width, height } => *width as u64 * *height as u64 * 2,
+        }
+    }
+
+    /// Returns the resolution tagged in a provider file name, or `None` if the name has no tag,
+    /// or contradictory ones.
+    ///
+    /// The tags are the words of the name, separated by `.`, `_`, `-` or spaces, in any case:
+    /// a word ending with `GL1` (as `SRTMGL1`) or starting with `1arc` is SRTM1, a word ending
+    /// with `GL3` or starting with `3arc` is SRTM3. This skips the size detection of
+    /// `Resolution::try_from(path)` for the downloads named after their product. The names read
+    /// by [`Tiles`] (`N49W002.hgt`) carry no tag: their resolution is always detected from
+    /// their size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Resolution;
+    /// assert_eq!(Resolution::from_filename("N49W002.SRTMGL1.hgt"), Some(Resolution::SRTM1));
+    /// assert_eq!(Resolution::from_filename("n49_w002_3arc_v2.bil"), Some(Resolution::SRTM3));
+    /// assert_eq!(Resolution::from_filename("N49W002.hgt"), None);
+    /// ```
+    pub fn from_filename(name: &str) -> Option<Resolution> {
+        let mut tagged = None;
+        for word in name.split(['.', '_', '-', ' ']) {
+            let word = word.to_ascii_uppercase();
+            let resolution = if word.ends_with("GL1") || word.starts_with("1ARC") {
+                Resolution::SRTM1
+            } else if word.ends_with("GL3") || word.starts_with("3ARC") {
+                Resolution::SRTM3
+            } else {
+                continue;
+            };
+            if tagged.is_some_and(|tagged| tagged != resolution) {
+                return None;
+            }
+            tagged = Some(resolution);
+        }
+        tagged
+    }
+
+    /// Returns the ground area, in square meters, of a cell centered at the given latitude.
+    ///
+    /// A cell spans one sample spacing in latitude and longitude (1 or 3 arc-seconds for the
+    /// SRTM tiles). Its area
+    /// is computed on the WGS84 ellipsoid (semi-major axis a = 6378137 m, flattening
+    /// f = 1 / 298.257223563), as the area of the ellipsoidal quadrilateral between the
+    /// latitudes φ1 and φ2 of the cell edges, for a longitude span Δλ:
+    ///
+    /// A = b² Δλ / 2 · (q(φ2) - q(φ1)),
+    /// with q(φ) = sin φ / (1 - e² sin² φ) + 1 / (2e) · ln((1 + e sin φ) / (1 - e sin φ)),
+    /// b = a (1 - f) and e² = f (2 - f).
+    ///
+    /// Unlike the spherical `cos(lat)` approximation, this stays accurate at high latitudes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Resolution;
+    /// let area = Resolution::SRTM3.cell_area_m2(0.0);
+    /// assert!((area - 8548.0).abs() < 5.0);
+    /// ```
+    pub fn cell_area_m2(&self, lat: f32) -> f64 {
+        const A: f64 = 6378137.0;
+        const F: f64 = 1.0 / 298.257223563;
+        let b = A * (1.0 - F);
+        let e2 = F * (2.0 - F);
+        let e = e2.sqrt();
+        let q = |phi: f64| {
+            let sin = phi.sin();
+            sin / (1.0 - e2 * sin * sin) + ((1.0 + e * sin) / (1.0 - e * sin)).ln() / (2.0 * e)
+        };
+
+        let (spacing_lat, spacing_lng) = self.spacing();
+        let edge = |lat: f64| lat.clamp(-90.0, 90.0).to_radians();
+        let (south, north) = (
+            edge(lat as f64 - spacing_lat / 2.0),
+            edge(lat as f64 + spacing_lat / 2.0),
+        );
+        b * b * spacing_lng.to_radians() / 2.0 * (q(north) - q(south))
+    }
+
+    /// Returns the ground spacing, in meters, between two samples at the given latitude, as
+    /// (east-west, north-south).
+    ///
+    /// The nominal 30 m (or 90 m) only holds north-south: the east-west spacing shrinks with
+    /// the latitude. Both are computed from the radii of curvature of the WGS84 ellipsoid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Resolution;
+    /// let (east_west, north_south) = Resolution::SRTM1.cell_size_m(60.0);
+    /// assert!((east_west - 15.5).abs() < 0.1);
+    /// assert!((north_south - 30.9).abs() < 0.1);
+    /// ```
+    pub fn cell_size_m(&self, lat: f32) -> (f32, f32) {
+        const A: f64 = 6378137.0;
+        const E2: f64 = 0.00669437999014;
+        let phi = (lat as f64).clamp(-90.0, 90.0).to_radians();
+        let w = 1.0 - E2 * phi.sin().powi(2);
+        let meridian = A * (1.0 - E2) / w.powf(1.5);
+        let parallel = A / w.sqrt() * phi.cos();
+        let (spacing_lat, spacing_lng) = self.spacing();
+        (
+            (parallel * spacing_lng.to_radians()) as f32,
+            (meridian * spacing_lat.to_radians()) as f32,
+        )
+    }
+
+    /// Returns the spacing, in degrees, between two samples as (latitude, longitude).
+    fn spacing(&self) -> (f64, f64) {
+        (
+            1.0 / (self.height() - 1) as f64,
+            1.0 / (self.width() - 1) as f64,
+        )
+    }
+}
+
+/// Wraps a longitude into [-180, 180): 180° is the western edge of W180.
+fn wrap_lng(lng: f32) -> f32 {
+    if (-180.0..180.0).contains(&lng) {
+        lng
+    } else {
+        // wrapping from 0° keeps the precision of the longitudes next to the antimeridian
+        let wrapped = lng.rem_euclid(360.0);
+        if wrapped < 180.0 {
+            wrapped
+        } else {
+            wrapped - 360.0
+        }
+    }
+}
+
+/// Returns the offset, in degrees, of a longitude from the western edge `lng0` of a tile.
+///
+/// The longitude is wrapped first, and the offset across the antimeridian: 180° is 1° east of
+/// the western edge of E179, not 359° west of it.
+fn lng_offset(lng: f32, lng0: i32) -> f32 {
+    (wrap_lng(lng) - lng0 as f32).rem_euclid(360.0)
+}
+
+/// Convention selecting the tile of a geoposition lying exactly on a tile edge.
+///
+/// Tiles overlap by one sample on their edges, so both conventions read the same height: they
+/// only differ by the file needed to read it.
+///
+/// With the default `NorthEast` convention, the tile is selected from the floored degrees: lat
+/// 50.0 belongs to N50 and lng -1.0 belongs to W001. Note that any point south of the equator
+/// belongs to a southern tile: lat -0.1 belongs to S01, lat 0.0 belongs to N00.
+///
+/// The sign of zero is ignored: -0.0 is on the equator (or the prime meridian) as 0.0 is, so
+/// that (-0.0, -0.0) belongs to N00E000 as (0.0, 0.0) does. The antimeridian is the western edge
+/// of W180, whether written 180.0 or -180.0.
+///
+/// With the `SouthWest` convention, a point on an edge belongs to the tile south or west of it,
+/// as with elevation services selecting the tile from `ceil(v) - 1`: lat 50.0 belongs to N49,
+/// lng -1.0 belongs to W002 and (0.0, 0.0) belongs to S01W001. Points inside a tile are not
+/// affected.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TileBoundary {
+    /// Points on an edge belong to the tile north (or east) of this edge.
+    #[default]
+    NorthEast,
+    /// Points on an edge belong to the tile south (or west) of this edge.
+    SouthWest,
+}
+
+/// Convention locating the samples of a tile for the interpolation.
+///
+/// With the default `Point` convention, samples are points on the tile grid: the sample (x, y)
+/// of the tile whose south-west corner is (lat0, lng0) is at (lat0 + 1 - y / n, lng0 + x / n),
+/// with `n = side - 1`. This is how GDAL reads .hgt files: their geotransform is pixel-is-area,
+/// with its origin half a pixel north-west of the tile corner, which puts the cell centers on
+/// the grid points. Interpolations then match `gdallocationinfo` with bilinear resampling.
+///
+/// With the `Area` convention, samples are the centers of cells whose north-west corner is on
+/// the tile grid: the sample (x, y) is at (lat0 + 1 - (y + 0.5) / n, lng0 + (x + 0.5) / n). This
+/// matches rasters georeferenced with their origin at the tile corner, like exports ignoring
+/// the half pixel offset of SRTM. Heights are then interpolated half a cell south-east of the
+/// `Point` ones.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum PixelConvention {
+    /// Samples are on the grid points (pixel-is-point, GDAL reading .hgt files).
+    #[default]
+    Point,
+    /// Samples are at the centers of the grid cells (pixel-is-area, origin at the tile corner).
+    Area,
+}
+
+/// Selection of the sample of [`Tiles::elevation_nearest`] for a geoposition exactly halfway
+/// between two samples.
+///
+/// Only exact midpoints are concerned, which are rare with f32 coordinates: others always
+/// select the nearest sample. Pixels are counted eastward and northward, so that rounding up
+/// selects the eastern column and the northern row.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TieBreak {
+    /// Round down: the western column, the southern row.
+    Floor,
+    /// Round up, as [`Tiles::elevation`] does (half away from zero): the eastern column, the
+    /// northern row.
+    #[default]
+    Ceil,
+    /// Round to the even pixel index, as IEEE 754 and numpy do.
+    Even,
+}
+
+impl TieBreak {
+    /// Rounds a fractional pixel index (a non-negative value).
+    fn round(&self, pixel: f32) -> f32 {
+        if pixel.fract() != 0.5 {
+            return pixel.round();
+        }
+        match self {
+            TieBreak::Floor => pixel.floor(),
+            TieBreak::Ceil => pixel.ceil(),
+            TieBreak::Even => pixel.round_ties_even(),
+        }
+    }
+}
+
+/// Interpolation method of [`Tiles::elevation_interp`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Interp {
+    /// The sample nearest to the geoposition, as [`Tiles::elevation`].
+    Nearest,
+    /// Bilinear interpolation of the four surrounding samples, as
+    /// [`Tiles::elevation_interpolated`]: voids are interpolated as heights.
+    #[default]
+    Bilinear,
+    /// Bilinear interpolation of the four surrounding samples, where each void corner is
+    /// replaced by the inverse distance weighting of the valid samples within `search` cells
+    /// of it (a `(2 * search + 1)²` window, without crossing the tile edges).
+    ///
+    /// The heights then fade smoothly into the voids, instead of jumping like a
+    /// renormalization over the valid corners. A void corner without any valid sample in its
+    /// window is left out, the weights of the other corners being renormalized. Up to
+    /// `(2 * search + 1)²` samples are read for each void corner, 4 when no corner is a void,
+    /// and the heights in large void patches are best-effort estimates.
+    BilinearVoidAware {
+        /// Radius of the search window around void corners, in cells.
+        search: u32,
+    },
+}
+
+impl TileBoundary {
+    /// Returns the integer degrees (lat, lng) of the south-west corner of the tile containing
+    /// the given geoposition.
+    ///
+    /// Both the tile name and the pixel coordinates are derived from this degree, so a point
+    /// lying exactly on a tile edge always resolves to the same tile for both.
+    /// Longitudes are wrapped into [-180, 180) first, and the degree again: the antimeridian
+    /// belongs to E179 with the `SouthWest` convention.
+    fn origin(&self, lat: f32, lng: f32) -> (i32, i32) {
+        let lng0 = self.degree(wrap_lng(lng));
+        (self.degree(lat), (lng0 + 180).rem_euclid(360) - 180)
+    }
+
+    /// Returns the integer degree of the south (or west) edge of the tile containing `v`.
+    fn degree(&self, v: f32) -> i32 {
+        match self {
+            TileBoundary::NorthEast => v.floor() as i32,
+            TileBoundary::SouthWest => v.ceil() as i32 - 1,
+        }
+    }
+
+    /// Returns the south-west corners of the tiles covering a bounding box, from north-west to
+    /// south-east.
+    ///
+    /// The latitudes of the corners are in any order, but the box spans eastward from the
+    /// longitude of `from` to the one of `to`: a box whose `from` is east of its `to` crosses
+    /// the antimeridian. Longitudes are wrapped after the enumeration, so that a box reaching
+    /// 180° covers W180.
+    fn bbox_origins(&self, from: (f32, f32), to: (f32, f32)) -> Vec<(i32, i32)> {
+        let (south, north) = (self.degree(from.0.min(to.0)), self.degree(from.0.max(to.0)));
+        let (west, mut east) = (self.degree(from.1), self.degree(to.1));
+        if from.1 > to.1 {
+            east += 360;
+        }
+        (south..=north)
+            .rev()
+            .flat_map(|lat| (west..=east).map(move |lng| (lat, (lng + 180).rem_euclid(360) - 180)))
+            .collect()
+    }
+
+    /// Returns the name of the tile containing the geoposition with this convention.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::TileBoundary;
+    /// assert_eq!(TileBoundary::NorthEast.tile_name(50.0, -1.0), "N50W001.hgt");
+    /// assert_eq!(TileBoundary::SouthWest.tile_name(50.0, -1.0), "N49W002.hgt");
+    /// ```
+    pub fn tile_name(&self, lat: f32, lng: f32) -> String {
+        origin_name(self.origin(lat, lng))
+    }
+}
+
+/// Returns the name of the tile containing the geoposition, as read by [`Tiles::new`].
+///
+/// This uses the default [`TileBoundary::NorthEast`] convention.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::tile_name;
+/// assert_eq!(tile_name(49.1, -1.6), "N49W002.hgt");
+/// assert_eq!(tile_name(-0.1, 0.0), "S01E000.hgt");
+/// ```
+pub fn tile_name(lat: f32, lng: f32) -> String {
+    srtm_file_name(lat, lng)
+}
+
+/// Returns the names of the tiles covering a bounding box, from north-west to south-east, as
+/// read by [`Tiles::new`].
+///
+/// This is the geographic enumeration of [`Tiles::tiles_for_bbox`], without any filesystem
+/// access, e.g. to list the tiles to download. It uses the default [`TileBoundary::NorthEast`]
+/// convention: a box reaching 180° of longitude needs the W180 tiles. The box spans eastward
+/// from `from` to `to`, across the antimeridian if `from` is east of `to`.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::tiles_in_bbox;
+/// let names = tiles_in_bbox((49.5, -1.7), (50.1, 0.4));
+/// assert_eq!(names, ["N50W002.hgt", "N50W001.hgt", "N50E000.hgt",
+///                    "N49W002.hgt", "N49W001.hgt", "N49E000.hgt"]);
+/// assert_eq!(tiles_in_bbox((-16.5, 179.5), (-16.2, 180.0)), ["S17E179.hgt", "S17W180.hgt"]);
+/// assert_eq!(tiles_in_bbox((-16.2, 179.5), (-16.5, -179.5)), ["S17E179.hgt", "S17W180.hgt"]);
+/// ```
+pub fn tiles_in_bbox(from: (f32, f32), to: (f32, f32)) -> Vec<String> {
+    TileBoundary::NorthEast
+        .bbox_origins(from, to)
+        .into_iter()
+        .map(origin_name)
+        .collect()
+}
+
+/// Returns the byte offset, within its tile, of the sample nearest to the geoposition.
+///
+/// The tile is the one named by [`tile_name`], with the default [`TileBoundary`] convention.
+/// The offset is `(x + y × side) × 2`, for the pixel (x, y) counted from the north-west corner
+/// of the tile: the big-endian `i16` sample is the two bytes at this offset of the .hgt file.
+/// No IO is done, e.g. to read samples from a tile mapped in memory by the caller.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::{sample_offset, Resolution};
+/// // south-west corner of N49W002.hgt: first sample of the last row
+/// assert_eq!(sample_offset(49.0, -2.0, Resolution::SRTM3), 1200 * 1201 * 2);
+/// // then the next sample to the east
+/// assert_eq!(sample_offset(49.0, -1.999, Resolution::SRTM3), 1200 * 1201 * 2 + 2);
+/// ```
+pub fn sample_offset(lat: f32, lng: f32, resolution: Resolution) -> u64 {
+    let (x, y) = srtm_file_coord(lat, lng, tile_origin(lat, lng), resolution);
+    (x as u64 + y as u64 * resolution.width() as u64) * 2
+}
+
+/// Returns the elevation (height) from latitude and longitude, read from the bytes of the tile
+/// containing the geoposition.
+///
+/// This is the IO-free core of [`Tiles::elevation`], for tiles already in memory (e.g. mapped
+/// from a flash storage by a firmware): the resolution is detected from the length of `tile`,
+/// which must be the content of the .hgt file named by [`tile_name`] for this geoposition
+/// (this is not checked), and the sample is decoded at [`sample_offset`].
+///
+/// # Errors
+///
+/// * `SrtmError::ResolutionError` if the length of `tile` is not a tile size.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::{elevation_from_slice, Resolution};
+/// let tile = vec![0; Resolution::SRTM3.file_size() as usize];
+/// assert_eq!(elevation_from_slice(&tile, 49.1, -1.6).unwrap(), 0);
+/// assert!(elevation_from_slice(&tile[1..], 49.1, -1.6).is_err());
+/// ```
+pub fn elevation_from_slice(tile: &[u8], lat: f32, lng: f32) -> Result<i16, SrtmError> {
+    let resolution = Resolution::try_from(tile.len() as u64)?;
+    let offset = sample_offset(lat, lng, resolution) as usize;
+    Ok(i16::from_be_bytes([tile[offset], tile[offset + 1]]))
+}
+
+/// Returns the integer degrees of the south-west corner of the tile containing the geoposition,
+/// with the default convention.
+fn tile_origin(lat: f32, lng: f32) -> (i32, i32) {
+    TileBoundary::default().origin(lat, lng)
+}
+
+/// Returns the name of the tile whose south-west corner is at the given integer degrees.
+fn origin_name(origin: (i32, i32)) -> String {
+    origin_name_in(origin, &mut [0; 32]).to_string()
+}
+
+/// Writes the name of the tile whose south-west corner is at the given integer degrees into
+/// `buffer`, without allocating, and returns it.
+fn origin_name_in((lat, lng): (i32, i32), buffer: &mut [u8; 32]) -> &str {
+    let ns = if lat >= 0 { "N" } else { "S" };
+    let ew = if lng >= 0 { "E" } else { "W" };
+    let mut cursor = &mut buffer[..];
+    write!(cursor, "{}{:02}{}{:03}.hgt", ns, lat.abs(), ew, lng.abs())
+        .expect("tile names fit in 32 bytes");
+    let len = 32 - cursor.len();
+    std::str::from_utf8(&buffer[..len]).expect("tile names are ASCII")
+}
+
+/// Opens a tile from its archive entry, or from the first directory containing it.
+///
+/// # Errors
+///
+/// * `SrtmError::MissingTile` if no directory contains the tile.
+fn open_tile_in(
+    archived: Option<&ArchivedTile>,
+    directories: &[PathBuf],
+    name: &str,
+) -> Result<TileFile> {
+    if let Some(tile) = archived {
+        return Ok(TileFile::open_archived(tile)?);
+    }
+    for path in directories
+        .iter()
+        .filter_map(|directory| tile_path(directory, name))
+    {
+        match TileFile::open(&path) {
+            Ok(file) => return Ok(file),
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
         }
     }
+    Err(SrtmError::MissingTile(name.to_string()).into())
+}
+
+/// Returns the integer degrees of the south-west corner of a tile from its name, as formatted by
+/// [`origin_name`], or `None` if this is not a tile name.
+fn name_origin(name: &str) -> Option<(i32, i32)> {
+    let name = name.strip_suffix(".hgt")?;
+    if name.len() != 7 || !name.is_ascii() {
+        return None;
+    }
+    let degree = |sign: &str, digits: &str, positive: &str, negative: &str| {
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let value: i32 = digits.parse().ok()?;
+        match sign {
+            _ if sign == positive => Some(value),
+            _ if sign == negative => Some(-value),
+            _ => None,
+        }
+    };
+    let lat = degree(&name[0..1], &name[1..3], "N", "S")?;
+    let lng = degree(&name[3..4], &name[4..7], "E", "W")?;
+    ((-90..90).contains(&lat) && (-180..180).contains(&lng)).then_some((lat, lng))
 }
 
 /// generate srtm file name containing elevation for the given geoposition
 fn srtm_file_name(lat: f32, lng: f32) -> String {
-    let clean = |v: f32| v.floor().abs();
-    let ns = if lat >= 0.0 { "N" } else { "S" };
-    let ew = if lng >= 0.0 && lng < 180.0 { "E" } else { "W" };
-    format!("{}{:02}{}{:03}.hgt", ns, clean(lat), ew, clean(lng)).to_string()
+    origin_name(tile_origin(lat, lng))
 }
 
 /// Generate srtm pixel coordinates for the given geoposition.
 /// - x coordinate increase when the longitude goes east (except at 180°).
 /// - y coordinate increase with the latitude goes north.
-fn srtm_file_coord(lat: f32, lng: f32, resolution: Resolution) -> (u32, u32) {
-    let side = resolution.side() - 1;
-    let pixel_index = |v: f32| ((v - v.floor()) * side as f32).round() as u32;
-    (pixel_index(lng), side - pixel_index(lat))
+///
+/// The pixel is computed relative to the `origin` of the tile (see `TileBoundary::origin`).
+/// With the default convention, a point on the western edge is column 0 and a point on the
+/// southern edge is the last row (`height - 1`) of the selected tile. These edge samples
+/// overlap the neighboring tiles' eastern column and northern row, with the same value.
+fn srtm_file_coord(lat: f32, lng: f32, origin: (i32, i32), resolution: Resolution) -> (u32, u32) {
+    srtm_file_coord_with(lat, lng, origin, resolution, TieBreak::default())
+}
+
+/// Generate srtm pixel coordinates for the given geoposition, breaking the ties between two
+/// samples with `tie`.
+///
+/// See [`srtm_file_coord`].
+fn srtm_file_coord_with(
+    lat: f32,
+    lng: f32,
+    origin: (i32, i32),
+    resolution: Resolution,
+    tie: TieBreak,
+) -> (u32, u32) {
+    let (n, m) = (resolution.width() - 1, resolution.height() - 1);
+    let (lat0, lng0) = origin;
+    let pixel_index = |offset: f32, side: u32| tie.round(offset * side as f32) as u32;
+    (
+        pixel_index(lng_offset(lng, lng0), n),
+        m - pixel_index(lat - lat0 as f32, m),
+    )
 }
 
-/// A **Tiles** structure retains the directory path of the tiles.
+/// A **Tiles** structure retains the directory paths of the tiles.
 /// It works as a context the retrieve values by calling `tiles.elevation(lat, lng)`.
 ///
 /// This structure also handles opened files to prevent reopening a file each call.
@@ -150,17 +775,304 @@ fn srtm_file_coord(lat: f32, lng: f32, resolution: Resolution) -> (u32, u32) {
 /// ## Methods
 ///
 /// - `pub fn new<P: AsRef<Path>>(directory: P) -> Self`
+/// - `pub fn builder<P: AsRef<Path>>(directory: P) -> TilesBuilder`
 /// - `pub fn elevation(&self, lat: f32, lng: f32) -> Result<i16>`
+/// - `pub fn elevation_interpolated(&self, lat: f32, lng: f32) -> Result<f32>`
 #[derive(Debug)]
 pub struct Tiles {
-    directory: PathBuf,
-    handles: RefCell<HashMap<String, File>>,
+    directories: Vec<PathBuf>,
+    config: Config,
+    archived: HashMap<String, ArchivedTile>,
+    handles: RefCell<HashMap<String, OpenedTile>>,
+    corrupt: RefCell<HashSet<String>>,
+    reference: Cell<Option<((f32, f32), i16)>>,
+    counters: Cell<CacheCounters>,
+    overrides: RefCell<Overrides>,
+    histograms: RefCell<HashMap<String, Histogram>>,
+}
+
+/// Samples set with [`Tiles::override_sample`], by tile name and pixel.
+type Overrides = HashMap<(String, (u32, u32)), i16>;
+
+/// Clones the directories, the configuration and the overridden samples, with an empty cache.
+///
+/// Clones do not share the opened tiles: each one opens its own files on its first lookups,
+/// and has its own [`Tiles::cache_stats`]. A Tiles object can be sent to another thread but
+/// not shared between threads, so that cloning a template for each worker thread is the
+/// lock-free way to read tiles in parallel.
+///
+/// # Example
+///
+/// ```no_run
+/// use easy_srtm::Tiles;
+/// let template = Tiles::builder("the_folder_path").strict(true).build();
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let tiles = template.clone();
+///         std::thread::spawn(move || tiles.elevation(49.1 + i as f32 * 0.1, -1.6))
+///     })
+///     .collect();
+/// ```
+impl Clone for Tiles {
+    fn clone(&self) -> Self {
+        Tiles {
+            directories: self.directories.clone(),
+            config: self.config.clone(),
+            archived: self.archived.clone(),
+            handles: RefCell::default(),
+            corrupt: RefCell::default(),
+            reference: Cell::new(None),
+            counters: Cell::default(),
+            overrides: self.overrides.clone(),
+            histograms: RefCell::default(),
+        }
+    }
+}
+
+/// An opened tile file, with its resolution.
+#[derive(Debug)]
+struct OpenedTile {
+    file: TileFile,
+    resolution: Resolution,
+    last_access: Instant,
+}
+
+/// Options set with a [`TilesBuilder`].
+#[derive(Debug, Clone)]
+struct Config {
+    boundary: TileBoundary,
+    strict: bool,
+    vertical_datum: Option<String>,
+    void: i16,
+    void_below: Option<i16>,
+    allow_missing: bool,
+    pixel: PixelConvention,
+    read_buffer: usize,
+    dimensions: Option<Resolution>,
+    audit: Option<debug::Audit>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            boundary: TileBoundary::default(),
+            strict: false,
+            vertical_datum: None,
+            void: VOID,
+            void_below: None,
+            allow_missing: false,
+            pixel: PixelConvention::Point,
+            read_buffer: 0,
+            dimensions: None,
+            audit: None,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the resolution of a tile from its file size, the SRTM ones first.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::ResolutionError` if the size is neither a SRTM one nor the configured
+    ///   dimensions.
+    fn resolution(&self, filesize: u64) -> Result<Resolution, SrtmError> {
+        Resolution::try_from(filesize).or_else(|e| match self.dimensions {
+            Some(dimensions) if dimensions.file_size() == filesize => Ok(dimensions),
+            _ => Err(e),
+        })
+    }
+}
+
+/// A **TilesBuilder** configures a [`Tiles`] before its creation.
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::{TileBoundary, Tiles};
+/// let tiles = Tiles::builder("/dev/null")
+///     .boundary(TileBoundary::SouthWest)
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct TilesBuilder {
+    directories: Vec<PathBuf>,
+    config: Config,
+}
+
+impl TilesBuilder {
+    /// Sets the convention selecting the tile of points lying exactly on tile edges.
+    ///
+    /// Defaults to [`TileBoundary::NorthEast`].
+    pub fn boundary(mut self, boundary: TileBoundary) -> Self {
+        self.config.boundary = boundary;
+        self
+    }
+
+    /// Sets the convention locating the samples for the interpolation.
+    ///
+    /// See [`PixelConvention`]. Defaults to `Point`.
+    pub fn pixel_convention(mut self, pixel: PixelConvention) -> Self {
+        self.config.pixel = pixel;
+        self
+    }
+
+    /// Sets the size, in bytes, of the read-ahead buffer of the region reads ([`Tiles::grid`],
+    /// [`Tiles::rows`], [`Tiles::fill_region`] and the methods built on them).
+    ///
+    /// Without buffer, each row of a region is read from its tile with its own system calls.
+    /// With a buffer, a row read also fetches the following bytes of the tile, which hold the
+    /// next rows: a 1 MiB buffer serves about 145 rows of a SRTM1 tile, or 436 of a SRTM3
+    /// one, from memory. Each opened tile keeps its own buffer. The geoposition lookups, which
+    /// read random samples, are not buffered. Defaults to 0 (no buffer).
+    pub fn read_buffer(mut self, size: usize) -> Self {
+        self.config.read_buffer = size;
+        self
+    }
+
+    /// Sets a sink receiving an [`AuditRecord`] for every successful lookup of
+    /// [`Tiles::elevation`] (and of the methods built on it, as [`Tiles::elevations`]), e.g. to
+    /// keep an audit trail of the samples behind published heights.
+    ///
+    /// The sink is called after the sample is read, from the thread of the lookup, and is
+    /// shared by the clones of the tiles. Failed lookups are not recorded. Without sink, the
+    /// lookups do not build any record.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("the_folder_path")
+    ///     .audit(|record| {
+    ///         let (x, y) = record.sample.pixel;
+    ///         println!("{},{},{},{},{},{}", record.lat, record.lng, record.sample.tile, x, y,
+    ///                  record.sample.height);
+    ///     })
+    ///     .build();
+    /// let altitude = tiles.elevation(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn audit<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(&AuditRecord) + Send + Sync + 'static,
+    {
+        self.config.audit = Some(debug::Audit(std::sync::Arc::new(sink)));
+        self
+    }
+
+    /// Sets the dimensions of the tiles which are neither SRTM1 nor SRTM3, as
+    /// [`Resolution::Dimensions`].
+    ///
+    /// Tiles still cover one degree, with `width` columns and `height` rows (an overlapped
+    /// column and row included), e.g. 1801 × 3601 for 1 arc-second products halving their
+    /// columns at high latitudes. Files of `width × height × 2` bytes are read with these
+    /// dimensions, the SRTM sizes being detected first. As with mixed resolutions, a region
+    /// cannot span SRTM tiles and tiles of these dimensions.
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `height` is lower than 2.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("the_folder_path").dimensions(1801, 3601).build();
+    /// let altitude = tiles.elevation(65.1, 25.4)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn dimensions(mut self, width: u32, height: u32) -> Self {
+        assert!(
+            width >= 2 && height >= 2,
+            "tiles of {}×{} samples do not span a degree",
+            width,
+            height
+        );
+        self.config.dimensions = Some(Resolution::Dimensions { width, height });
+        self
+    }
+
+    /// Sets the vertical datum of the heights, as reported by [`Tiles::vertical_datum`].
+    ///
+    /// Defaults to "EGM96", the datum of SRTM files. Override it when the tiles were converted,
+    /// e.g. to "WGS84 ellipsoidal" heights.
+    pub fn vertical_datum<S: Into<String>>(mut self, datum: S) -> Self {
+        self.config.vertical_datum = Some(datum.into());
+        self
+    }
+
+    /// Enables the validation of the tiles content.
+    ///
+    /// In strict mode, each tile is scanned once when first opened, and rejected with
+    /// `SrtmError::CorruptTile` if it holds only zeros or voids, a common sign of a failed
+    /// download. Tile sizes are always validated. Defaults to false.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// Sets the height of the samples without data (voids).
+    ///
+    /// Defaults to [`VOID`] (-32768), the value of SRTM files. Void-aware lookups like
+    /// [`Tiles::elevation_opt`] compare samples to this value.
+    pub fn void_value(mut self, void: i16) -> Self {
+        self.config.void = void;
+        self
+    }
+
+    /// Treats the heights lower than `threshold` as voids, as well as the void value.
+    ///
+    /// Void-filled data sets may hold implausible deep negative heights (e.g. -500 m inland):
+    /// void-aware lookups like [`Tiles::elevation_opt`] skip them with this option. Real
+    /// terrain may lie below the sea level, down to -430 m at the Dead Sea shore, so choose
+    /// the threshold for the covered area. Defaults to none.
+    pub fn void_below(mut self, threshold: i16) -> Self {
+        self.config.void_below = Some(threshold);
+        self
+    }
+
+    /// Reads the samples of missing tiles as voids in region reads ([`Tiles::grid`],
+    /// [`Tiles::rows`], [`Tiles::fill_region`] and the methods built on them) and in
+    /// [`Tiles::elevations`], instead of failing with `SrtmError::MissingTile`.
+    ///
+    /// This suits mosaics of large areas where missing tiles are expected, like oceans. The
+    /// tile containing the first corner of a region must still be present, to select the
+    /// resolution. Defaults to false.
+    pub fn allow_missing(mut self, allow: bool) -> Self {
+        self.config.allow_missing = allow;
+        self
+    }
+
+    /// Adds a directory to search tiles in, after the previous ones.
+    ///
+    /// Each tile is read from the first directory containing it.
+    pub fn directory<P: AsRef<Path>>(mut self, directory: P) -> Self {
+        self.directories.push(directory.as_ref().to_path_buf());
+        self
+    }
+
+    /// Returns the configured Tiles object.
+    pub fn build(self) -> Tiles {
+        Tiles {
+            directories: self.directories,
+            config: self.config,
+            archived: HashMap::default(),
+            handles: RefCell::new(HashMap::default()),
+            corrupt: RefCell::new(HashSet::default()),
+            reference: Cell::new(None),
+            counters: Cell::default(),
+            overrides: RefCell::default(),
+            histograms: RefCell::default(),
+        }
+    }
 }
+
 impl Tiles {
     /// Returns a Tiles object referencing a directory as SRTM files source.
     ///
     /// This directory should contain all .hgt files needed for requested lat/lng elevation.
     /// Those files have to be present with their original names.
+    /// Use [`Tiles::builder`] to search tiles in several directories.
     ///
     /// # Arguments
     ///
@@ -175,50 +1087,1454 @@ impl Tiles {
     ///
     /// ```
     pub fn new<P: AsRef<Path>>(directory: P) -> Self {
-        Self {
-            directory: directory.as_ref().to_path_buf(),
-            handles: RefCell::new(HashMap::default()),
+        Self::builder(directory).build()
+    }
+
+    /// Returns a Tiles object referencing a directory as SRTM files source, after checking that
+    /// this directory exists.
+    ///
+    /// Unlike [`Tiles::new`], which never touches the filesystem, a wrong path fails here
+    /// rather than at the first lookup. Tiles are still opened lazily.
+    ///
+    /// # Errors
+    ///
+    /// * IO errors, if the directory does not exist or cannot be accessed.
+    /// * `SrtmError::InvalidArgument` if the path is not a directory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::open("the_folder_path")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        let directory = directory.as_ref();
+        let metadata = std::fs::metadata(directory)
+            .with_context(|| format!("cannot open tiles directory {:?}", directory))?;
+        if !metadata.is_dir() {
+            return Err(
+                SrtmError::InvalidArgument(format!("{:?} is not a directory", directory)).into(),
+            );
         }
+        Ok(Self::new(directory))
     }
 
-    /// Returns the elevation (height) from latitude and longitude.
+    /// Returns a Tiles object referencing the directory named by the `SRTM_DIR` environment
+    /// variable as SRTM files source.
     ///
-    /// This method return the elevation of the nearest point without the elevation's true
-    /// position.
-    /// This means that the same height is returned for a square around the true geoposition for
+    /// Use [`Tiles::from_env_var`] to read another variable.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingEnvVar` if `SRTM_DIR` is not set.
+    /// * `SrtmError::InvalidArgument` if it does not name a directory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// // SRTM_DIR=/data/srtm my-cli
+    /// let tiles = Tiles::from_env()?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_var("SRTM_DIR")
+    }
+
+    /// Returns a Tiles object referencing the directory named by an environment variable as
+    /// SRTM files source.
+    ///
+    /// Only the process environment is read: load `.env` files (e.g. with `dotenv`) before
+    /// this call for their variables to be seen.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingEnvVar` if the variable is not set (or not unicode).
+    /// * `SrtmError::InvalidArgument` if it does not name a directory.
+    pub fn from_env_var(var: &str) -> Result<Self> {
+        let directory = std::env::var(var).map_err(|_| SrtmError::MissingEnvVar(var.into()))?;
+        if !Path::new(&directory).is_dir() {
+            return Err(SrtmError::InvalidArgument(format!(
+                "{}={} is not a directory",
+                var, directory
+            ))
+            .into());
+        }
+        Ok(Self::new(directory))
+    }
+
+    /// Returns a builder to configure a Tiles object referencing a directory as SRTM files
+    /// source.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The path of the directory containing SRTM files.
+    pub fn builder<P: AsRef<Path>>(directory: P) -> TilesBuilder {
+        TilesBuilder {
+            directories: vec![directory.as_ref().to_path_buf()],
+            config: Config::default(),
+        }
+    }
+
+    /// Returns the name of the tile containing the geoposition, which has to be present in the
+    /// directory to read its elevation.
+    ///
+    /// This depends on the configured [`TileBoundary`] for points lying on tile edges.
+    pub fn tile_name(&self, lat: f32, lng: f32) -> String {
+        self.config.boundary.tile_name(lat, lng)
+    }
+
+    /// Returns the vertical datum of the heights: "EGM96" unless overridden with
+    /// [`TilesBuilder::vertical_datum`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Tiles;
+    /// assert_eq!(Tiles::new("/dev/null").vertical_datum(), "EGM96");
+    /// let tiles = Tiles::builder("/dev/null")
+    ///     .vertical_datum("WGS84 ellipsoidal")
+    ///     .build();
+    /// assert_eq!(tiles.vertical_datum(), "WGS84 ellipsoidal");
+    /// ```
+    pub fn vertical_datum(&self) -> &str {
+        self.config.vertical_datum.as_deref().unwrap_or("EGM96")
+    }
+
+    /// Returns the horizontal datum of the geopositions: "WGS84".
+    pub fn horizontal_datum(&self) -> &str {
+        "WGS84"
+    }
+
+    /// Returns the elevation (height) from latitude and longitude.
+    ///
+    /// This method return the elevation of the nearest point without the elevation's true
+    /// position.
+    /// This means that the same height is returned for a square around the true geoposition for
     /// the height.
+    ///
+    /// See [`Tiles::elevation_checked`] to prevent swapping the latitude and the longitude.
     pub fn elevation(&self, lat: f32, lng: f32) -> Result<i16> {
-        let filename = srtm_file_name(lat, lng);
+        Ok(self.elevation_with_resolution(lat, lng)?.0)
+    }
+
+    /// Overrides the sample nearest to the geoposition with `value`, for the following lookups.
+    ///
+    /// The tile files are left untouched: overrides are held in memory only, until
+    /// [`Tiles::clear_overrides`], e.g. to remove known spikes or fill voids at runtime. They
+    /// are kept by [`Tiles::refresh`] and copied by clones. [`Tiles::elevation`] and the
+    /// methods built on it (as [`Tiles::elevation_opt`], [`Tiles::elevations`],
+    /// [`Tiles::elevation_nearest`] or [`Tiles::elevation_best`], for the copy read by
+    /// [`Tiles::elevation`]) return the overridden values; region reads, interpolations and
+    /// [`Tiles::elevation_all_resolutions`] keep reading the files. An override on a tile edge
+    /// only applies to the tile selected for it (see [`TileBoundary`]).
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`Tiles::elevation`]: the tile is opened to locate the sample.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // a radar spike on the summit
+    /// tiles.override_sample(49.1, -1.6, 112)?;
+    /// assert_eq!(tiles.elevation(49.1, -1.6)?, 112);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn override_sample(&self, lat: f32, lng: f32, value: i16) -> Result<()> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let pixel = self.with_tile(origin, |_, resolution| {
+            Ok(srtm_file_coord(lat, lng, origin, resolution))
+        })?;
+        self.overrides
+            .borrow_mut()
+            .insert((origin_name(origin), pixel), value);
+        self.reference.set(None);
+        Ok(())
+    }
+
+    /// Removes all the overrides set with [`Tiles::override_sample`].
+    pub fn clear_overrides(&self) {
+        self.overrides.borrow_mut().clear();
+        self.reference.set(None);
+    }
+
+    /// Returns the override of the sample at the pixel of a tile, if any.
+    fn overridden_sample(&self, origin: (i32, i32), pixel: (u32, u32)) -> Option<i16> {
+        let overrides = self.overrides.borrow();
+        if overrides.is_empty() {
+            return None;
+        }
+        overrides.get(&(origin_name(origin), pixel)).copied()
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, breaking the ties between
+    /// two samples with `tie`.
+    ///
+    /// This is [`Tiles::elevation`], for a geoposition exactly halfway between two samples:
+    /// match the selection of another tool to get the same heights. The default tie break is
+    /// the one of [`Tiles::elevation`]. Overrides (see [`Tiles::override_sample`]) and the audit
+    /// sink apply as they do for [`Tiles::elevation`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{TieBreak, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // halfway between the columns 37 and 38 of a SRTM3 tile
+    /// let west = tiles.elevation_nearest(49.1, -1.96875, TieBreak::Floor)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_nearest(&self, lat: f32, lng: f32, tie: TieBreak) -> Result<i16> {
+        Ok(self.elevation_with_tie(lat, lng, tie)?.0)
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, with the resolution of the
+    /// tile it is read from.
+    fn elevation_with_resolution(&self, lat: f32, lng: f32) -> Result<(i16, Resolution)> {
+        self.elevation_with_tie(lat, lng, TieBreak::default())
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, breaking the ties between
+    /// two samples with `tie`, with the resolution of the tile it is read from.
+    fn elevation_with_tie(&self, lat: f32, lng: f32, tie: TieBreak) -> Result<(i16, Resolution)> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let (height, resolution, pixel) = self.with_tile(origin, |file, resolution| {
+            let (x, y) = srtm_file_coord_with(lat, lng, origin, resolution, tie);
+            let height = match self.overridden_sample(origin, (x, y)) {
+                Some(height) => height,
+                None => read_sample(file, resolution, x, y)?,
+            };
+            Ok((height, resolution, (x, y)))
+        })?;
+        if let Some(audit) = &self.config.audit {
+            (audit.0)(&AuditRecord {
+                lat,
+                lng,
+                resolution,
+                sample: Snapped {
+                    tile: origin_name(origin),
+                    pixel,
+                    height,
+                },
+            });
+        }
+        Ok((height, resolution))
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, read from the finest tile
+    /// available, with the resolution of this tile.
+    ///
+    /// In directories mixing resolutions, the same tile name may be present as SRTM1 in a
+    /// directory and as SRTM3 in another one, while [`Tiles::elevation`] always reads the first
+    /// copy found. Here the copies are tried in this order:
+    ///
+    /// 1. the SRTM1 copies, then the SRTM3 ones,
+    /// 2. for the same resolution, in the search order (see [`TilesBuilder::directory`]).
+    ///
+    /// The first copy whose sample is not a void is returned, so that the voids of a SRTM1 tile
+    /// are filled from a SRTM3 copy. If every copy is a void, the void of the finest copy is
+    /// returned. The sample of the copy read by [`Tiles::elevation`] is overridden as by
+    /// [`Tiles::override_sample`]; the other copies are read from their files.
+    ///
+    /// Only the first copy is kept opened: when it is a SRTM1 tile with a valid sample, no
+    /// other file is opened. Otherwise the copies are read with
+    /// [`Tiles::elevation_all_resolutions`].
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if no copy of the tile is found.
+    /// * `SrtmError::ResolutionError` if a copy has an invalid size.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Resolution, Tiles};
+    /// let tiles = Tiles::builder("srtm3").directory("srtm1").build();
+    /// let (resolution, height) = tiles.elevation_best(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_best(&self, lat: f32, lng: f32) -> Result<(Resolution, i16)> {
+        let (height, resolution) = self.elevation_with_resolution(lat, lng)?;
+        if resolution == Resolution::SRTM1 && !self.is_void_height(height) {
+            return Ok((resolution, height));
+        }
+
+        let mut copies = self.elevation_all_resolutions(lat, lng)?;
+        // the first copy is the one read by `elevation`, with its override
+        copies[0] = (resolution, height);
+        copies.sort_by_key(|(resolution, _)| *resolution != Resolution::SRTM1);
+        let valid = copies
+            .iter()
+            .find(|(_, height)| !self.is_void_height(*height));
+        Ok(*valid.unwrap_or(&copies[0]))
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, read from a single canonical
+    /// tile for each sample.
+    ///
+    /// With [`Tiles::elevation`], the tile is selected from the geoposition itself: 49.99999
+    /// reads the northern row of N49 while 50.0 reads the southern row of N50. Those rows overlap
+    /// and hold the same samples in consistent data sets, but not when the neighboring tiles
+    /// differ (e.g. tiles from different sources or resolutions). Here the geoposition is first
+    /// snapped to its nearest sample, and the tile is selected from this sample with the
+    /// configured [`TileBoundary`]: every position snapping to an edge sample reads it from the
+    /// same tile.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if the tile containing the geoposition or the canonical tile
+    ///   is missing.
+    pub fn elevation_canonical(&self, lat: f32, lng: f32) -> Result<i16> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let snapped = self.with_tile(origin, |_, resolution| {
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            Ok(sample_position(origin, resolution, x, y))
+        })?;
+        let canonical = self.config.boundary.origin(snapped.0, snapped.1);
+        self.with_tile(canonical, |file, resolution| {
+            let (x, y) = srtm_file_coord(snapped.0, snapped.1, canonical, resolution);
+            read_sample(file, resolution, x, y)
+        })
+    }
+
+    /// Returns the ground distance, in meters, between the geoposition and the sample read for
+    /// it by [`Tiles::elevation`].
+    ///
+    /// This is the horizontal uncertainty of the height returned for this geoposition: up to
+    /// half the diagonal of a cell (about 21 m for SRTM1, 65 m for SRTM3 at the equator). The
+    /// distance is computed with [`haversine_m`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let height = tiles.elevation(49.1, -1.6)?;
+    /// let error = tiles.snap_error_m(49.1, -1.6)?;
+    /// println!("{} m (±{:.0} m horizontal)", height, error);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn snap_error_m(&self, lat: f32, lng: f32) -> Result<f32> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let sample = self.with_tile(origin, |_, resolution| {
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            Ok(sample_position(origin, resolution, x, y))
+        })?;
+        Ok(haversine_m((lat, lng), sample))
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, or `None` if the sample is a
+    /// void.
+    ///
+    /// Voids are samples equal to the configured void value (see [`TilesBuilder::void_value`]).
+    pub fn elevation_opt(&self, lat: f32, lng: f32) -> Result<Option<i16>> {
+        let height = self.elevation(lat, lng)?;
+        Ok(Some(height).filter(|&height| !self.is_void_height(height)))
+    }
+
+    /// Returns true if the sample nearest to the geoposition is a void.
+    ///
+    /// See [`Tiles::elevation_opt`].
+    pub fn is_void(&self, lat: f32, lng: f32) -> Result<bool> {
+        Ok(self.elevation_opt(lat, lng)?.is_none())
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, or `default` if the tile is
+    /// missing or the sample is a void.
+    ///
+    /// This suits regions where missing tiles are expected, like oceans.
+    ///
+    /// # Panics
+    ///
+    /// On any other error (e.g. a permission denied or a corrupted tile). Use
+    /// [`Tiles::elevation`] to handle those errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Tiles;
+    /// // without N00W030.hgt in the directory
+    /// let tiles = Tiles::new("the_folder_path");
+    /// assert_eq!(tiles.elevation_or(0.0, -30.0, 0), 0);
+    /// ```
+    pub fn elevation_or(&self, lat: f32, lng: f32, default: i16) -> i16 {
+        match self.elevation_opt(lat, lng) {
+            Ok(None) => default,
+            Ok(Some(height)) => height,
+            Err(e) if matches!(e.downcast_ref(), Some(SrtmError::MissingTile(_))) => default,
+            Err(e) => panic!("cannot read elevation at ({}, {}): {:#}", lat, lng, e),
+        }
+    }
+
+    /// Returns the elevation (height) of the geoposition relative to the `reference`
+    /// geoposition, i.e. `elevation(lat, lng) - elevation(reference)`.
+    ///
+    /// The height of the last reference is kept, so that successive calls with the same
+    /// reference (e.g. the home point of a drone) read a single sample each.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if the sample of the geoposition or of the reference is a void.
+    /// * The errors of [`Tiles::elevation`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let home = (49.1, -1.6);
+    /// let above_home = tiles.elevation_relative(49.12, -1.58, home)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_relative(&self, lat: f32, lng: f32, reference: (f32, f32)) -> Result<f32> {
+        let valid = |lat, lng| -> Result<i16> {
+            self.elevation_opt(lat, lng)?
+                .ok_or_else(|| SrtmError::NoData { lat, lng }.into())
+        };
+        let base = match self.reference.get() {
+            Some((cached, height)) if cached == reference => height,
+            _ => {
+                let height = valid(reference.0, reference.1)?;
+                self.reference.set(Some((reference, height)));
+                height
+            }
+        };
+        Ok(valid(lat, lng)? as f32 - base as f32)
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, or a timeout error if the
+    /// tile cannot be opened within `timeout`.
+    ///
+    /// The tile is opened by a watchdog thread: on a timeout, the thread is left to finish (or
+    /// block) on its own and its file is dropped, so that a later call may succeed. Opening is
+    /// the only guarded step, as it is the one which blocks on an unreachable network mount.
+    /// Once opened, the tile is kept as by [`Tiles::elevation`], and reading its samples is not
+    /// guarded: both backends (directories and tar archives, see `Tiles::from_tar`) read
+    /// blocking files, which cannot be interrupted. On a local disk, the guard is effectively a
+    /// no-op.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::Timeout` if opening the tile took longer than `timeout`.
+    /// * The errors of [`Tiles::elevation`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// use std::time::Duration;
+    /// let tiles = Tiles::new("/mnt/nfs/srtm");
+    /// let h = tiles.elevation_with_timeout(49.1, -1.6, Duration::from_millis(200))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_with_timeout(&self, lat: f32, lng: f32, timeout: Duration) -> Result<i16> {
+        let name = origin_name(self.config.boundary.origin(lat, lng));
+        let opened = self.handles.borrow().contains_key(&name);
+        if !opened && !self.corrupt.borrow().contains(&name) {
+            self.count(|counters| counters.misses += 1);
+            let (archived, directories) =
+                (self.archived.get(&name).cloned(), self.directories.clone());
+            let (sender, receiver) = mpsc::channel();
+            let task = name.clone();
+            thread::spawn(move || {
+                let _ = sender.send(open_tile_in(archived.as_ref(), &directories, &task));
+            });
+            let file = match receiver.recv_timeout(timeout) {
+                Ok(file) => file?,
+                Err(_) => return Err(SrtmError::Timeout(name).into()),
+            };
+            self.cache_tile(&name, file)?;
+            // the lookup was counted as a miss above, not as a hit on the tile just cached
+            let counters = self.counters.get();
+            let height = self.elevation(lat, lng);
+            self.counters.set(counters);
+            return height;
+        }
+        self.elevation(lat, lng)
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, linearly interpolated from the
+    /// four samples surrounding the geoposition.
+    ///
+    /// The four corners never span two tiles: the last row and column of a tile overlap its
+    /// neighbors. The interpolated heights are thus continuous across tile edges, as long as
+    /// the overlapping samples of neighboring tiles are identical. See [`bilinear`] for the
+    /// weighting convention, and [`PixelConvention`] for the position of the samples.
+    pub fn elevation_interpolated(&self, lat: f32, lng: f32) -> Result<f32> {
+        let (lat, lng) = self.interpolation_position(lat, lng)?;
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            read_interpolated(file, lat, lng, origin, resolution)
+        })
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, with the given
+    /// interpolation method.
+    ///
+    /// See [`Interp`] for the methods and their cost.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` with [`Interp::BilinearVoidAware`], if no valid sample is found
+    ///   around the void corners and every corner is a void.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Interp, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // coastlines without holes
+    /// let altitude = tiles.elevation_interp(49.1, -1.6, Interp::BilinearVoidAware { search: 2 })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_interp(&self, lat: f32, lng: f32, interp: Interp) -> Result<f32> {
+        let search = match interp {
+            Interp::Nearest => return Ok(self.elevation(lat, lng)? as f32),
+            Interp::Bilinear => return self.elevation_interpolated(lat, lng),
+            Interp::BilinearVoidAware { search } => search as i64,
+        };
+        let (lat, lng) = self.interpolation_position(lat, lng)?;
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            let (x, y, fx, fy) = srtm_file_cell(lat, lng, origin, resolution);
+            let (fx, fy) = (fx as f64, fy as f64);
+            let corners = [
+                ((x, y), (1.0 - fx) * (1.0 - fy)),
+                ((x + 1, y), fx * (1.0 - fy)),
+                ((x, y - 1), (1.0 - fx) * fy),
+                ((x + 1, y - 1), fx * fy),
+            ];
+            let (last_x, last_y) = (
+                resolution.width() as i64 - 1,
+                resolution.height() as i64 - 1,
+            );
+            let (mut sum, mut weights) = (0.0, 0.0);
+            for ((cx, cy), weight) in corners {
+                let height = read_sample(file, resolution, cx, cy)?;
+                let height = if !self.is_void_height(height) {
+                    Some(height as f64)
+                } else {
+                    // inverse distance weighting of the valid samples around the void corner
+                    let (cx, cy) = (cx as i64, cy as i64);
+                    let (mut idw_sum, mut idw_weights) = (0.0, 0.0);
+                    for ny in (cy - search).max(0)..=(cy + search).min(last_y) {
+                        for nx in (cx - search).max(0)..=(cx + search).min(last_x) {
+                            let sample = read_sample(file, resolution, nx as u32, ny as u32)?;
+                            if !self.is_void_height(sample) {
+                                let w = 1.0 / ((nx - cx).pow(2) + (ny - cy).pow(2)) as f64;
+                                idw_sum += sample as f64 * w;
+                                idw_weights += w;
+                            }
+                        }
+                    }
+                    Some(idw_sum / idw_weights).filter(|_| idw_weights > 0.0)
+                };
+                if let Some(height) = height {
+                    sum += height * weight;
+                    weights += weight;
+                }
+            }
+            if weights > 0.0 {
+                Ok((sum / weights) as f32)
+            } else {
+                Err(SrtmError::NoData { lat, lng }.into())
+            }
+        })
+    }
+
+    /// Returns the geoposition to interpolate the tiles at, for the configured
+    /// [`PixelConvention`].
+    fn interpolation_position(&self, lat: f32, lng: f32) -> Result<(f32, f32)> {
+        match self.config.pixel {
+            PixelConvention::Point => Ok((lat, lng)),
+            PixelConvention::Area => {
+                // the samples are half a cell south-east of their grid point
+                let origin = self.config.boundary.origin(lat, lng);
+                let (spacing_lat, spacing_lng) =
+                    self.with_tile(origin, |_, resolution| Ok(resolution.spacing()))?;
+                Ok((
+                    (lat as f64 + spacing_lat / 2.0) as f32,
+                    (lng as f64 - spacing_lng / 2.0) as f32,
+                ))
+            }
+        }
+    }
+
+    /// Returns the best elevation (height) available at the geoposition, in meters.
+    ///
+    /// The height is computed from the tile containing the geoposition, in this order:
+    ///
+    /// 1. interpolated from the four surrounding samples, as [`Tiles::elevation_interpolated`],
+    ///    if none of them is a void,
+    /// 2. otherwise interpolated from the valid corners only, their bilinear weights being
+    ///    renormalized to sum up to 1,
+    /// 3. otherwise the valid sample nearest to the geoposition, within 3 cells (in both
+    ///    directions) of the nearest sample, without crossing the tile edges.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if every sample within that radius is a void.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let altitude: f64 = tiles.elevation_robust(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_robust(&self, lat: f32, lng: f32) -> Result<f64> {
+        const RADIUS: i64 = 3;
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            let (x, y, fx, fy) = srtm_file_cell(lat, lng, origin, resolution);
+            let (fx, fy) = (fx as f64, fy as f64);
+            let corners = [
+                ((x, y), (1.0 - fx) * (1.0 - fy)),
+                ((x + 1, y), fx * (1.0 - fy)),
+                ((x, y - 1), (1.0 - fx) * fy),
+                ((x + 1, y - 1), fx * fy),
+            ];
+            let (mut sum, mut weights) = (0.0, 0.0);
+            for ((cx, cy), weight) in corners {
+                let height = read_sample(file, resolution, cx, cy)?;
+                if !self.is_void_height(height) {
+                    sum += height as f64 * weight;
+                    weights += weight;
+                }
+            }
+            if weights > 0.0 {
+                return Ok(sum / weights);
+            }
+
+            let (last_x, last_y) = (
+                resolution.width() as i64 - 1,
+                resolution.height() as i64 - 1,
+            );
+            let (px, py) = (x as f64 + fx, y as f64 - fy);
+            let (nx, ny) = srtm_file_coord(lat, lng, origin, resolution);
+            let (nx, ny) = (nx as i64, ny as i64);
+            let mut nearest: Option<(f64, i16)> = None;
+            for cy in (ny - RADIUS).max(0)..=(ny + RADIUS).min(last_y) {
+                for cx in (nx - RADIUS).max(0)..=(nx + RADIUS).min(last_x) {
+                    let distance = (cx as f64 - px).powi(2) + (cy as f64 - py).powi(2);
+                    if nearest.is_some_and(|(d, _)| d <= distance) {
+                        continue;
+                    }
+                    let height = read_sample(file, resolution, cx as u32, cy as u32)?;
+                    if !self.is_void_height(height) {
+                        nearest = Some((distance, height));
+                    }
+                }
+            }
+            match nearest {
+                Some((_, height)) => Ok(height as f64),
+                None => Err(SrtmError::NoData { lat, lng }.into()),
+            }
+        })
+    }
+
+    /// Returns the distances, in cells, from the sample nearest to the geoposition to the
+    /// north, south, east and west edges of its tile.
+    ///
+    /// A sample on an edge is at distance 0 from it, and the distances to opposite edges sum up
+    /// to `side - 1` (3600 for SRTM1, 1200 for SRTM3). The tile is opened to detect its
+    /// resolution, but no sample is read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // N49W002.hgt is a SRTM3 tile
+    /// let (north, south, east, west) = tiles.edge_distance_cells(49.25, -1.5)?;
+    /// assert_eq!((north, south, east, west), (900, 300, 600, 600));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn edge_distance_cells(&self, lat: f32, lng: f32) -> Result<(u32, u32, u32, u32)> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |_, resolution| {
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            let (n, m) = (resolution.width() - 1, resolution.height() - 1);
+            Ok((y, m - y, n - x, x))
+        })
+    }
+
+    /// Returns the lowest and highest heights of the tile containing the geoposition, as
+    /// `(min, max)`, voids excluded.
+    ///
+    /// The tile is scanned by chunks, without decoding it into a whole buffer as
+    /// [`Tiles::load_tile`] does, e.g. to compute the height range of a data set for a color
+    /// ramp.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if the tile holds only voids.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (min, max) = tiles.tile_extent(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn tile_extent(&self, lat: f32, lng: f32) -> Result<(i16, i16)> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let (min, max) = self.with_tile(origin, |file, resolution| {
+            let (mut min, mut max) = (i16::MAX, i16::MIN);
+            scan_samples(file, resolution, |samples| {
+                for &height in samples {
+                    if !self.is_void_height(height) {
+                        (min, max) = (min.min(height), max.max(height));
+                    }
+                }
+                true
+            })?;
+            Ok((min, max))
+        })?;
+        if min > max {
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+        Ok((min, max))
+    }
+
+    /// Returns the resolution of the tile containing the geoposition.
+    ///
+    /// The tile is opened and kept in the cache, as with [`Tiles::elevation`], but no sample is
+    /// read: a following lookup in this tile reuses the opened file and its resolution.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Resolution, Tiles};
+    /// let tiles = Tiles::builder("srtm1").directory("srtm3").build();
+    /// let stride = match tiles.resolution_at(49.1, -1.6)? {
+    ///     Resolution::SRTM1 => 3,
+    ///     Resolution::SRTM3 | Resolution::Dimensions { .. } => 1,
+    /// };
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn resolution_at(&self, lat: f32, lng: f32) -> Result<Resolution> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |_, resolution| Ok(resolution))
+    }
+
+    /// Returns the resolution of the tile containing the geoposition and the `(x, y)` pixel of
+    /// the sample nearest to it, as read by [`Tiles::elevation`].
+    ///
+    /// `x` is the column, counted eastward from the western edge of the tile, and `y` the row,
+    /// counted southward from its northern edge: the sample is at byte offset
+    /// `2 * (x + y * width)` of the tile file. As with [`Tiles::resolution_at`], the tile is
+    /// opened and cached but no sample is read, e.g. to precompute the indices of a batch of
+    /// positions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (resolution, x, y) = tiles.pixel_of(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn pixel_of(&self, lat: f32, lng: f32) -> Result<(Resolution, u32, u32)> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |_, resolution| {
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            Ok((resolution, x, y))
+        })
+    }
+
+    /// Returns the ground spacing, in meters, between the samples of the tile containing the
+    /// geoposition, as (east-west, north-south).
+    ///
+    /// This is [`Resolution::cell_size_m`] for the resolution of that tile, e.g. to report the
+    /// actual horizontal resolution of the heights rather than the nominal one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (east_west, north_south) = tiles.horizontal_resolution_m(49.1, -1.6)?;
+    /// println!("±{:.0} m horizontal", east_west.max(north_south) / 2.0);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn horizontal_resolution_m(&self, lat: f32, lng: f32) -> Result<(f32, f32)> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |_, resolution| Ok(resolution.cell_size_m(lat)))
+    }
+
+    /// Returns the elevation read from every copy of the tile containing the geoposition, with
+    /// the resolution of each copy, in the search order: the archive entry first (see
+    /// `Tiles::from_tar`), then the directories.
+    ///
+    /// Unlike [`Tiles::elevation`], this does not stop at the first copy of the tile, so that
+    /// SRTM1 and SRTM3 copies of a tile can be compared. Those copies are opened for this call
+    /// only.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if neither the archive nor a directory contains the tile.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("srtm1").directory("srtm3").build();
+    /// for (resolution, height) in tiles.elevation_all_resolutions(49.1, -1.6)? {
+    ///     println!("{:?}: {}", resolution, height);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_all_resolutions(&self, lat: f32, lng: f32) -> Result<Vec<(Resolution, i16)>> {
+        let origin = self.config.boundary.origin(lat, lng);
+        let name = origin_name(origin);
+        let mut heights = Vec::new();
+        if let Some(tile) = self.archived.get(&name) {
+            let mut file = TileFile::open_archived(tile)?;
+            let resolution = self.config.resolution(file.len())?;
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            heights.push((resolution, read_sample(&mut file, resolution, x, y)?));
+        }
+        for path in self
+            .directories
+            .iter()
+            .filter_map(|directory| tile_path(directory, &name))
+        {
+            let mut file = match File::open(path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let resolution = self.config.resolution(file.metadata()?.len())?;
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            heights.push((resolution, read_sample(&mut file, resolution, x, y)?));
+        }
+        if heights.is_empty() {
+            return Err(SrtmError::MissingTile(name).into());
+        }
+        Ok(heights)
+    }
+
+    /// Returns the names of the tiles covering a bounding box, from north-west to south-east.
+    ///
+    /// These are the tiles read by [`Tiles::grid`] for the same corners: check they are all
+    /// present with [`Tiles::has_tile`] before a large read. A box reaching 180° of longitude
+    /// needs the W180 tiles, as named by [`tile_name`]. The box spans eastward from `from` to
+    /// `to`, across the antimeridian if `from` is east of `to`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let names = tiles.tiles_for_bbox((49.5, -1.7), (50.1, 0.4));
+    /// assert_eq!(names[0], "N50W002.hgt");
+    /// assert_eq!(names.len(), 6);
+    /// ```
+    pub fn tiles_for_bbox(&self, from: (f32, f32), to: (f32, f32)) -> Vec<String> {
+        self.config
+            .boundary
+            .bbox_origins(from, to)
+            .into_iter()
+            .map(origin_name)
+            .collect()
+    }
+
+    /// Returns the names of the tiles covering a bounding box which are missing from the
+    /// directories, from north-west to south-east.
+    ///
+    /// This is [`Tiles::tiles_for_bbox`] filtered by [`Tiles::has_tile`]: download those tiles
+    /// or clip the region before reading it.
+    pub fn missing_in_bbox(&self, from: (f32, f32), to: (f32, f32)) -> Vec<String> {
+        self.tiles_for_bbox(from, to)
+            .into_iter()
+            .filter(|name| !self.has_tile(name))
+            .collect()
+    }
+
+    /// Returns the size, in bytes, of the samples of the tiles covering a bounding box.
+    ///
+    /// Whole tiles are counted (2 bytes per sample, 25.9 MB for SRTM1 and 2.9 MB for SRTM3),
+    /// as they are held by [`Tiles::into_memory`] or [`Tiles::load_tile`]: this is an upper
+    /// bound for [`Tiles::grid`], which holds the samples inside the box only. Missing tiles
+    /// hold no samples and are not counted. The tiles are opened to detect their resolution.
+    ///
+    /// # Errors
+    ///
+    /// * IO errors other than missing tiles, or invalid tiles.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// if tiles.region_bytes((52.0, -5.0), (42.0, 8.0))? > 1 << 30 {
+    ///     // too large to be loaded in memory
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn region_bytes(&self, from: (f32, f32), to: (f32, f32)) -> Result<u64> {
+        let mut total = 0;
+        for name in self.tiles_for_bbox(from, to) {
+            match self.with_named_tile(&name, |_, resolution| Ok(resolution.file_size())) {
+                Ok(size) => total += size,
+                Err(e) if matches!(e.downcast_ref(), Some(SrtmError::MissingTile(_))) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Returns true if one of the directories contains the tile.
+    pub fn has_tile(&self, name: &str) -> bool {
+        self.archived.contains_key(name)
+            || self
+                .directories
+                .iter()
+                .any(|directory| tile_path(directory, name).is_some_and(|path| path.is_file()))
+    }
+
+    /// Reads the sample at the (x, y) pixel coordinates of a raw elevation grid file, with a
+    /// caller-supplied side length.
+    ///
+    /// This bypasses the size-based resolution detection, for non-standard exports: the file
+    /// must hold `side × side` big-endian `i16` samples, row-major from the north-west corner,
+    /// like .hgt files. The file is opened for this call only, at `path` (which is not searched
+    /// in the directories).
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `x` or `y` is not lower than `side`.
+    /// * `SrtmError::FileTooShort` if the file holds less than `side² × 2` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let height = tiles.read_sample_with("export_2001.raw", 2001, 1000, 1000)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn read_sample_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        side: u32,
+        x: u32,
+        y: u32,
+    ) -> Result<i16> {
+        if x >= side || y >= side {
+            return Err(SrtmError::InvalidArgument(format!(
+                "pixel ({}, {}) is outside of a {} samples side",
+                x, y, side
+            ))
+            .into());
+        }
+        let mut file = File::open(path)?;
+        let (len, needed) = (file.metadata()?.len(), side as u64 * side as u64 * 2);
+        if len < needed {
+            return Err(SrtmError::FileTooShort { len, needed }.into());
+        }
+        file.seek(SeekFrom::Start((x as u64 + y as u64 * side as u64) * 2))?;
+        Ok(file.read_i16::<BigEndian>()?)
+    }
+
+    /// Reads the sample at the (x, y) pixel coordinates of a tile, given its file name (e.g.
+    /// "N49W002.hgt", see [`Tiles::tile_name`]).
+    ///
+    /// `x` is the column from the west and `y` the row from the north, both from 0 to
+    /// `side - 1`. The tile is searched in the directories and kept opened, like the tiles of
+    /// the geoposition lookups.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `x` or `y` is not lower than the side of the tile.
+    /// * `SrtmError::MissingTile` if the tile is not found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let height = tiles.sample_by_pixel("N49W002.hgt", 600, 600)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn sample_by_pixel(&self, tile: &str, x: u32, y: u32) -> Result<i16> {
+        self.with_named_tile(tile, |file, resolution| {
+            let (width, height) = (resolution.width(), resolution.height());
+            if x >= width || y >= height {
+                return Err(SrtmError::InvalidArgument(format!(
+                    "pixel ({}, {}) is outside of tile {} of {}×{} samples",
+                    x, y, tile, width, height
+                ))
+                .into());
+            }
+            read_sample(file, resolution, x, y)
+        })
+    }
+
+    /// Opens the given tiles and keeps them in the cache, so that following lookups in those
+    /// tiles do not open any file.
+    ///
+    /// Returns the names of the missing tiles, which are skipped.
+    ///
+    /// # Errors
+    ///
+    /// * IO errors other than missing tiles, or invalid tiles.
+    pub fn preload<S: AsRef<str>>(&self, names: &[S]) -> Result<Vec<String>> {
+        let mut missing = Vec::new();
+        for name in names {
+            match self.with_named_tile(name.as_ref(), |_, _| Ok(())) {
+                Ok(()) => {}
+                Err(e) => match e.downcast::<SrtmError>()? {
+                    SrtmError::MissingTile(name) => missing.push(name),
+                    e => return Err(e.into()),
+                },
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Preloads the tiles containing the points of a track, e.g. read from a GPX file, so that
+    /// the following lookups along the track do not open any file.
+    ///
+    /// Each distinct tile is opened once. Returns the names of the missing tiles.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let track = vec![(49.1, -1.6), (49.2, -1.4), (49.3, -0.9)];
+    /// let missing = tiles.preload_track(&track)?;
+    /// if !missing.is_empty() {
+    ///     println!("download {:?}", missing);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn preload_track(&self, points: &[(f32, f32)]) -> Result<Vec<String>> {
+        let mut names: Vec<String> = points
+            .iter()
+            .map(|&(lat, lng)| self.tile_name(lat, lng))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        self.preload(&names)
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, read from a tile already
+    /// opened by this Tiles object.
+    ///
+    /// This is the fast path of [`Tiles::elevation`] for inner loops, once the tiles are
+    /// resident (see [`Tiles::preload`]): the directories are not searched, the opened tile is
+    /// looked up without allocating and no `Result` is returned.
+    ///
+    /// # Panics
+    ///
+    /// * If the tile containing the geoposition was not opened yet (or was forgotten by
+    ///   [`Tiles::refresh`]).
+    /// * If the sample cannot be read from the opened file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let missing = tiles.preload(&["N49W002.hgt"])?;
+    /// assert!(missing.is_empty());
+    /// let altitude = tiles.elevation_cached_unchecked(49.1, -1.6);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_cached_unchecked(&self, lat: f32, lng: f32) -> i16 {
+        let origin = self.config.boundary.origin(lat, lng);
+        let mut buffer = [0; 32];
+        let name = origin_name_in(origin, &mut buffer);
+        let mut handles = self.handles.borrow_mut();
+        let handle = handles
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("tile {} is not opened", name));
+        handle.last_access = Instant::now();
+        self.count(|counters| counters.hits += 1);
+        let (x, y) = srtm_file_coord(lat, lng, origin, handle.resolution);
+        read_sample(&mut handle.file, handle.resolution, x, y)
+            .unwrap_or_else(|e| panic!("cannot read tile {}: {:#}", name, e))
+    }
+
+    /// Forgets the opened tiles, their histograms (see [`Tiles::percentile_rank`]) and the tiles
+    /// rejected as corrupted, so that the tiles added to or replaced in the directories since
+    /// they were opened are read.
+    ///
+    /// Missing tiles are not cached: a tile added to a directory is found by the next lookup
+    /// anyway, unless a copy from a later directory was already opened. The directories are
+    /// not rescanned, as tiles are searched on lookups: refresh only checks that each of them
+    /// is still readable, after dropping the caches.
+    ///
+    /// # Errors
+    ///
+    /// * any IO error if one of the directories cannot be read. The caches are dropped anyway.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("the_folder_path").strict(true).build();
+    /// // ... a downloader replaces a corrupted tile
+    /// tiles.refresh()?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn refresh(&self) -> Result<()> {
+        let evicted = self.handles.borrow_mut().drain().count() as u64;
+        self.count(|counters| counters.evictions += evicted);
+        self.corrupt.borrow_mut().clear();
+        self.histograms.borrow_mut().clear();
+        self.reference.set(None);
+        // the listing is only opened to check that the directory is readable
+        for directory in &self.directories {
+            std::fs::read_dir(directory)
+                .with_context(|| format!("cannot read directory {:?}", directory))?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the height is a void for this configuration.
+    fn is_void_height(&self, height: i16) -> bool {
+        height == self.config.void
+            || self
+                .config
+                .void_below
+                .is_some_and(|threshold| height < threshold)
+    }
+
+    /// Returns the tiles present in more than one directory, with the paths of every copy.
+    ///
+    /// Tiles are read from the first directory containing them (the first path listed here),
+    /// the other copies are shadowed. Unreadable directories are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("srtm1").directory("srtm3").build();
+    /// for (name, paths) in tiles.conflicts() {
+    ///     println!("{} read from {:?}, shadowing {:?}", name, paths[0], &paths[1..]);
+    /// }
+    /// ```
+    pub fn conflicts(&self) -> Vec<(String, Vec<PathBuf>)> {
+        let mut copies: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for directory in &self.directories {
+            for (name, path) in tile_files(directory).unwrap_or_default() {
+                copies.entry(name).or_default().push(path);
+            }
+        }
+        let mut conflicts: Vec<_> = copies
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .collect();
+        conflicts.sort();
+        conflicts
+    }
+
+    /// Opens the tile from the archive or from the first directory containing it.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if no directory contains the tile.
+    fn open_tile(&self, name: &str) -> Result<TileFile> {
+        open_tile_in(self.archived.get(name), &self.directories, name)
+    }
+
+    /// Calls `f` with the opened tile whose south-west corner is `origin` and its resolution.
+    ///
+    /// The file is opened (and validated in strict mode) on the first call for this tile and
+    /// kept for the following ones.
+    fn with_tile<T, F>(&self, origin: (i32, i32), f: F) -> Result<T>
+    where
+        F: FnOnce(&mut TileFile, Resolution) -> Result<T>,
+    {
+        self.with_named_tile(&origin_name(origin), f)
+    }
+
+    /// Calls `f` with the opened tile of the given file name and its resolution.
+    ///
+    /// See [`Tiles::with_tile`].
+    fn with_named_tile<T, F>(&self, filename: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut TileFile, Resolution) -> Result<T>,
+    {
+        let filename = filename.to_string();
         let cachehit = self.handles.borrow().get(&filename).is_some();
 
-        if !cachehit {
-            let file = File::open(self.directory.join(filename.clone()))?;
-            self.handles.borrow_mut().insert(filename.clone(), file);
+        if cachehit {
+            self.count(|counters| counters.hits += 1);
+        } else {
+            self.count(|counters| counters.misses += 1);
+            if self.corrupt.borrow().contains(&filename) {
+                return Err(SrtmError::CorruptTile(filename).into());
+            }
+            let file = self.open_tile(&filename)?;
+            self.cache_tile(&filename, file)?;
+        }
+
+        let mut handles = self.handles.borrow_mut();
+        let handle = handles.get_mut(&filename).unwrap();
+        if cachehit {
+            handle.last_access = Instant::now();
         }
+        f(&mut handle.file, handle.resolution)
+    }
 
-        let height = self
-            .handles
+    /// Keeps an opened tile for the following lookups, after checking it in strict mode.
+    fn cache_tile(&self, filename: &str, mut file: TileFile) -> Result<()> {
+        let resolution = self.config.resolution(file.len())?;
+        if self.config.strict && !has_data(&mut file, resolution, self.config.void)? {
+            self.corrupt.borrow_mut().insert(filename.to_string());
+            return Err(SrtmError::CorruptTile(filename.to_string()).into());
+        }
+        let handle = OpenedTile {
+            file,
+            resolution,
+            last_access: Instant::now(),
+        };
+        self.handles
             .borrow_mut()
-            .get(&filename)
-            .map(|mut f| -> Result<i16> {
-                let resolution = Resolution::try_from(f.metadata()?.len())?;
-                let (x, y) = srtm_file_coord(lat, lng, resolution);
-                let index = x + y * resolution.side();
-                f.seek(SeekFrom::Start((index * 2) as u64))?;
-                Ok(f.read_i16::<BigEndian>()?)
-            })
-            .unwrap()?;
+            .insert(filename.to_string(), handle);
+        Ok(())
+    }
 
-        Ok(height)
+    // TODO fn to return the nearest geoposition having data and its height
+}
+
+/// Returns the geoposition (lat, lng) of the sample at the (x, y) pixel coordinates of a tile.
+fn sample_position(origin: (i32, i32), resolution: Resolution, x: u32, y: u32) -> (f32, f32) {
+    let (n, m) = (resolution.width() - 1, resolution.height() - 1);
+    (
+        origin.0 as f32 + (m - y) as f32 / m as f32,
+        origin.1 as f32 + x as f32 / n as f32,
+    )
+}
+
+/// Reads the sample at the (x, y) pixel coordinates of a tile.
+fn read_sample<R: Read + Seek>(
+    reader: &mut R,
+    resolution: Resolution,
+    x: u32,
+    y: u32,
+) -> Result<i16> {
+    let index = x as u64 + y as u64 * resolution.width() as u64;
+    reader.seek(SeekFrom::Start(index * 2))?;
+    Ok(reader.read_i16::<BigEndian>()?)
+}
+
+/// Reads the height at the geoposition of a tile, interpolated from the four surrounding
+/// samples.
+fn read_interpolated<R: Read + Seek>(
+    reader: &mut R,
+    lat: f32,
+    lng: f32,
+    origin: (i32, i32),
+    resolution: Resolution,
+) -> Result<f32> {
+    let (x, y, fx, fy) = srtm_file_cell(lat, lng, origin, resolution);
+    let mut corners = [0.0; 4];
+    for (corner, (cx, cy)) in
+        corners
+            .iter_mut()
+            .zip([(x, y), (x + 1, y), (x, y - 1), (x + 1, y - 1)])
+    {
+        *corner = read_sample(reader, resolution, cx, cy)? as f32;
     }
+    Ok(bilinear(corners, fx, fy))
+}
 
-    // TODO fn to return the interpolated (linear) height for this geoposition
+/// Returns true if a tile holds at least one sample which is neither 0 nor a void.
+///
+/// Tiles made only of zeros or voids are typical of failed downloads.
+fn has_data<R: Read + Seek>(reader: &mut R, resolution: Resolution, void: i16) -> Result<bool> {
+    let mut data = false;
+    scan_samples(reader, resolution, |samples| {
+        data = samples.iter().any(|&height| height != 0 && height != void);
+        !data
+    })?;
+    Ok(data)
+}
 
-    // TODO fn to return the nearest geoposition having data and its height
+/// Calls `f` with the successive chunks of the samples of a tile, until it returns false.
+///
+/// Reads stop at the end of the tile, without decoding the whole tile at once.
+fn scan_samples<R, F>(reader: &mut R, resolution: Resolution, mut f: F) -> Result<()>
+where
+    R: Read + Seek,
+    F: FnMut(&[i16]) -> bool,
+{
+    const CHUNK: u64 = 1 << 16;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut bytes = vec![0; CHUNK as usize];
+    let mut samples = vec![0; CHUNK as usize / 2];
+    let mut remaining = resolution.file_size();
+    while remaining > 0 {
+        let len = remaining.min(CHUNK) as usize;
+        reader.read_exact(&mut bytes[..len])?;
+        BigEndian::read_i16_into(&bytes[..len], &mut samples[..len / 2]);
+        if !f(&samples[..len / 2]) {
+            break;
+        }
+        remaining -= len as u64;
+    }
+    Ok(())
+}
+
+/// Returns the tile name of a .hgt file path, or `None` for other files.
+///
+/// Only the files whose extension is exactly `.hgt`, in any case, are tiles: GDAL sidecars
+/// (`.hgt.aux.xml`), `.prj` or `.tfw` files are not. Names are normalized to the form of
+/// [`tile_name`] (`n49w002.HGT` is the tile `N49W002.hgt`).
+fn hgt_name(path: &Path) -> Option<String> {
+    let is_hgt = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("hgt"));
+    let stem = path.file_stem()?.to_str()?;
+    is_hgt.then(|| format!("{}.hgt", stem.to_ascii_uppercase()))
+}
+
+/// Returns the path of a tile in a directory, or `None` if the directory does not contain it.
+///
+/// The canonical name is tried first, then the files whose [`hgt_name`] is the tile name, so
+/// that `n49w002.HGT` is the tile `N49W002.hgt` on case-sensitive filesystems too.
+fn tile_path(directory: &Path, name: &str) -> Option<PathBuf> {
+    let path = directory.join(name);
+    if path.exists() {
+        return Some(path);
+    }
+    tile_files(directory)
+        .ok()?
+        .into_iter()
+        .find_map(|(tile, path)| (tile == name).then_some(path))
+}
+
+/// Returns the names and paths of the .hgt files of a directory.
+///
+/// See [`hgt_name`] for the files listed.
+fn tile_files(directory: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        match hgt_name(&path) {
+            Some(name) if path.is_file() => files.push((name, path.clone())),
+            _ => {}
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the south-west sample (x, y) of the cell containing the geoposition, and the
+/// fractional position (fx eastward, fy northward) of the geoposition within this cell.
+fn srtm_file_cell(
+    lat: f32,
+    lng: f32,
+    origin: (i32, i32),
+    resolution: Resolution,
+) -> (u32, u32, f32, f32) {
+    let (n, m) = (resolution.width() - 1, resolution.height() - 1);
+    let (lat0, lng0) = origin;
+    let cell = |offset: f32, side: u32| {
+        let pixel = offset * side as f32;
+        let index = (pixel.floor() as u32).min(side - 1);
+        (index, pixel - index as f32)
+    };
+    let (x, fx) = cell(lng_offset(lng, lng0), n);
+    let (j, fy) = cell(lat - lat0 as f32, m);
+    (x, m - j, fx, fy)
+}
+
+/// Bilinear interpolation of the four corners of a cell.
+///
+/// This is the interpolation used by [`Tiles::elevation_interpolated`], exposed for callers
+/// reading the samples by themselves.
+///
+/// # Arguments
+///
+/// * `corners` - The corner heights, ordered south-west, south-east, north-west, north-east.
+/// * `fx` - The eastward fractional position within the cell, from 0 (west) to 1 (east).
+/// * `fy` - The northward fractional position within the cell, from 0 (south) to 1 (north).
+///
+/// # Example
+///
+/// ```
+/// use easy_srtm::bilinear;
+/// let (sw, se, nw, ne) = (100.0, 200.0, 300.0, 400.0);
+/// assert_eq!(bilinear([sw, se, nw, ne], 0.0, 0.0), sw);
+/// assert_eq!(bilinear([sw, se, nw, ne], 1.0, 1.0), ne);
+/// assert_eq!(bilinear([sw, se, nw, ne], 0.5, 0.5), 250.0);
+/// ```
+pub fn bilinear(corners: [f32; 4], fx: f32, fy: f32) -> f32 {
+    let [sw, se, nw, ne] = corners;
+    let south = sw + (se - sw) * fx;
+    let north = nw + (ne - nw) * fx;
+    south + (north - south) * fy
 }
 
 // UNIT TESTS
 
+/// Validate the resolutions tagged in provider file names
+#[test]
+fn it_detects_the_resolution_from_tagged_names() {
+    for name in [
+        "N49W002.SRTMGL1.hgt",
+        "N49W002.srtmgl1.hgt.zip",
+        "n49_w002_1arc_v3.tif",
+        "N49W002-1arcsec.hgt",
+    ] {
+        assert_eq!(
+            Resolution::from_filename(name),
+            Some(Resolution::SRTM1),
+            "{}",
+            name
+        );
+    }
+    for name in ["N49W002.SRTMGL3.hgt", "n49_w002_3arc_v2.bil"] {
+        assert_eq!(
+            Resolution::from_filename(name),
+            Some(Resolution::SRTM3),
+            "{}",
+            name
+        );
+    }
+    for name in [
+        "N49W002.hgt",
+        "N49W002.SRTMGL30.hgt",
+        "n49_w002_30arc.tif",
+        "N49W002.SRTMGL1.3arc.hgt",
+        "",
+    ] {
+        assert_eq!(Resolution::from_filename(name), None, "{}", name);
+    }
+}
+
+/// Validate the tile origins parsed from tile names
+#[test]
+fn it_parses_tile_origins_from_names() {
+    assert_eq!(name_origin("N49W002.hgt"), Some((49, -2)));
+    assert_eq!(name_origin("S01E000.hgt"), Some((-1, 0)));
+    assert_eq!(name_origin("S17W180.hgt"), Some((-17, -180)));
+    assert_eq!(name_origin(&origin_name((-56, 179))), Some((-56, 179)));
+    for name in [
+        "N49W002",
+        "N49W02.hgt",
+        "X49W002.hgt",
+        "N4+W002.hgt",
+        "N90E000.hgt",
+        "N00E180.hgt",
+    ] {
+        assert_eq!(name_origin(name), None, "{}", name);
+    }
+}
+
 /// Validate the srtm file name generation from lat lng
 #[test]
 fn it_generates_hgt_file_name_from_latlng() {
@@ -253,6 +2569,88 @@ fn it_generates_hgt_file_name_from_latlng() {
     // check(-91.0, 0.0, "S91E000");
 }
 
+/// Validate the tiles and pixels of the positions on the equator, the prime meridian and the
+/// antimeridian, with both boundary conventions and both signs of zero.
+#[test]
+fn it_selects_the_hemisphere_tiles_on_the_zero_edges() {
+    let n = Resolution::SRTM3.width() - 1;
+    // 179.99998° once wrapped, not 180°
+    let below_antimeridian = -180.00002f32;
+    let cases = [
+        // (lat, lng, north-east tile, north-east pixel, south-west tile, south-west pixel)
+        (0.0, 0.0, "N00E000.hgt", (0, n), "S01W001.hgt", (n, 0)),
+        (-0.0, -0.0, "N00E000.hgt", (0, n), "S01W001.hgt", (n, 0)),
+        (0.0, -0.0, "N00E000.hgt", (0, n), "S01W001.hgt", (n, 0)),
+        (-0.0, 0.0, "N00E000.hgt", (0, n), "S01W001.hgt", (n, 0)),
+        (-1.0, -1.0, "S01W001.hgt", (0, n), "S02W002.hgt", (n, 0)),
+        (1.0, 1.0, "N01E001.hgt", (0, n), "N00E000.hgt", (n, 0)),
+        (
+            -f32::EPSILON,
+            -f32::EPSILON,
+            "S01W001.hgt",
+            (n, 0),
+            "S01W001.hgt",
+            (n, 0),
+        ),
+        (
+            f32::EPSILON,
+            f32::EPSILON,
+            "N00E000.hgt",
+            (0, n),
+            "N00E000.hgt",
+            (0, n),
+        ),
+        (
+            45.5,
+            180.0,
+            "N45W180.hgt",
+            (0, n / 2),
+            "N45E179.hgt",
+            (n, n / 2),
+        ),
+        (
+            45.5,
+            -180.0,
+            "N45W180.hgt",
+            (0, n / 2),
+            "N45E179.hgt",
+            (n, n / 2),
+        ),
+        (
+            45.5,
+            below_antimeridian,
+            "N45E179.hgt",
+            (n, n / 2),
+            "N45E179.hgt",
+            (n, n / 2),
+        ),
+    ];
+    for (lat, lng, ne_name, ne_pixel, sw_name, sw_pixel) in cases {
+        for (boundary, name, pixel) in [
+            (TileBoundary::NorthEast, ne_name, ne_pixel),
+            (TileBoundary::SouthWest, sw_name, sw_pixel),
+        ] {
+            let origin = boundary.origin(lat, lng);
+            assert_eq!(
+                origin_name(origin),
+                name,
+                "{:?} ({}, {})",
+                boundary,
+                lat,
+                lng
+            );
+            assert_eq!(
+                srtm_file_coord(lat, lng, origin, Resolution::SRTM3),
+                pixel,
+                "{:?} ({}, {})",
+                boundary,
+                lat,
+                lng
+            );
+        }
+    }
+}
+
 /// Validate the mapping of lat lng to srtm file coordinates
 #[test]
 fn it_computes_hgt_elevation_coordinates_from_latlng() {
@@ -266,7 +2664,216 @@ fn it_computes_hgt_elevation_coordinates_from_latlng() {
     ];
 
     data.into_iter().for_each(|(lat, lng, x, y)| {
-        let result = srtm_file_coord(lat, lng, Resolution::SRTM1);
+        let result = srtm_file_coord(lat, lng, tile_origin(lat, lng), Resolution::SRTM1);
         assert!(result == (x, y), "failed for (l={:?}, g={:?})", lat, lng);
     });
 }
+
+/// Validate that points exactly on tile edges resolve to the sample GDAL reads for them.
+///
+/// GDAL georeferences a tile whose south-west corner is (lat0, lng0) with the origin
+/// (lng0 - 0.5 / n, lat0 + 1 + 0.5 / n) and a pixel size of 1 / n degree (n = side - 1).
+/// `gdallocationinfo -wgs84` on this tile thus reads the column `floor((lng - lng0) * n + 0.5)`
+/// and the row `floor((lat0 + 1 - lat) * n + 0.5)`.
+#[test]
+fn it_resolves_tile_edges_to_the_gdal_overlapping_sample() {
+    let n = Resolution::SRTM1.width() - 1;
+    let gdal_pixel = |lat: f32, lng: f32, (lat0, lng0): (i32, i32)| {
+        let col = ((lng as f64 - lng0 as f64) * n as f64 + 0.5).floor() as u32;
+        let row = ((lat0 as f64 + 1.0 - lat as f64) * n as f64 + 0.5).floor() as u32;
+        (col, row)
+    };
+    let position = |(lat0, lng0): (i32, i32), (x, y): (u32, u32)| {
+        let lat = lat0 as f64 + 1.0 - y as f64 / n as f64;
+        let lng = lng0 as f64 + x as f64 / n as f64;
+        (lat, (lng + 180.0).rem_euclid(360.0) - 180.0)
+    };
+
+    // (lat, lng, expected tile, expected pixel, neighbor tile also holding the sample)
+    let edges = vec![
+        (50.0, -1.0, "N50W001.hgt", (0, n), (49, -2)),
+        (50.0, -1.0, "N50W001.hgt", (0, n), (49, -1)),
+        (50.0, -1.0, "N50W001.hgt", (0, n), (50, -2)),
+        (49.0, -1.5, "N49W002.hgt", (n / 2, n), (48, -2)),
+        (49.5, -1.0, "N49W001.hgt", (0, n / 2), (49, -2)),
+        (0.0, 0.0, "N00E000.hgt", (0, n), (-1, -1)),
+        (-1.0, 1.0, "S01E001.hgt", (0, n), (-2, 0)),
+        (45.0, 180.0, "N45W180.hgt", (0, n), (44, 179)),
+    ];
+
+    edges
+        .into_iter()
+        .for_each(|(lat, lng, name, pixel, neighbor)| {
+            assert_eq!(srtm_file_name(lat, lng), name);
+            let coord = srtm_file_coord(lat, lng, tile_origin(lat, lng), Resolution::SRTM1);
+            assert_eq!(coord, pixel, "failed for (l={:?}, g={:?})", lat, lng);
+            assert_eq!(
+                position(tile_origin(lat, lng), coord),
+                position(neighbor, gdal_pixel(lat, lng, neighbor)),
+                "failed for (l={:?}, g={:?}) against {:?}",
+                lat,
+                lng,
+                neighbor
+            );
+        });
+}
+
+/// Validate the bilinear weighting of the corners
+#[test]
+fn it_interpolates_corners_bilinearly() {
+    let corners = [0.0, 10.0, 100.0, 1000.0];
+    let check = |fx, fy, expect: f32| {
+        let result = bilinear(corners, fx, fy);
+        assert!(
+            (result - expect).abs() < 1e-3,
+            "failed for (x={:?}, y={:?})",
+            fx,
+            fy
+        )
+    };
+
+    check(0.0, 0.0, 0.0);
+    check(1.0, 0.0, 10.0);
+    check(0.0, 1.0, 100.0);
+    check(1.0, 1.0, 1000.0);
+    check(0.5, 0.0, 5.0);
+    check(0.0, 0.5, 50.0);
+    check(
+        0.25,
+        0.75,
+        0.75 * (0.75 * 100.0 + 0.25 * 1000.0) + 0.25 * (0.25 * 10.0),
+    );
+}
+
+/// Validate the ellipsoidal cell areas against the meridian and parallel arc lengths
+#[test]
+fn it_computes_ellipsoidal_cell_areas() {
+    // meridian (m) and parallel (p) radii of curvature of WGS84 at the given latitude
+    let arcs = |lat: f64, resolution: Resolution| {
+        let (a, e2) = (6378137.0, 0.00669437999014);
+        let sin2 = lat.to_radians().sin().powi(2);
+        let m = a * (1.0 - e2) / (1.0 - e2 * sin2).powf(1.5);
+        let p = a / (1.0 - e2 * sin2).sqrt() * lat.to_radians().cos();
+        let spacing = (1.0 / (resolution.width() - 1) as f64).to_radians();
+        m * spacing * p * spacing
+    };
+
+    for resolution in [Resolution::SRTM1, Resolution::SRTM3] {
+        for lat in [0.0, 30.0, -45.0, 60.0, 80.0] {
+            let area = resolution.cell_area_m2(lat as f32);
+            let expect = arcs(lat, resolution);
+            assert!(
+                (area - expect).abs() / expect < 1e-4,
+                "failed for {:?} at lat {}",
+                resolution,
+                lat
+            );
+        }
+    }
+}
+
+/// Validate the sample spacings against the ellipsoidal cell areas
+#[test]
+fn it_computes_cell_sizes() {
+    let (east_west, north_south) = Resolution::SRTM3.cell_size_m(0.0);
+    assert!((east_west - 92.77).abs() < 0.01);
+    assert!((north_south - 92.15).abs() < 0.01);
+
+    for resolution in [Resolution::SRTM1, Resolution::SRTM3] {
+        for lat in [0.0, -30.0, 45.0, 80.0] {
+            let (east_west, north_south) = resolution.cell_size_m(lat);
+            let area = resolution.cell_area_m2(lat) as f32;
+            assert!((east_west * north_south - area).abs() / area < 1e-4);
+        }
+    }
+}
+
+/// Validate that the sample offsets are the positions read by the tiles
+#[test]
+fn it_computes_sample_offsets() {
+    let resolution = Resolution::SRTM3;
+    for (lat, lng) in [(49.5, -1.5), (49.123, -1.987), (-0.5, 179.99), (49.0, -2.0)] {
+        let mut bytes = vec![0; resolution.file_size() as usize];
+        let offset = sample_offset(lat, lng, resolution) as usize;
+        bytes[offset..offset + 2].copy_from_slice(&1234i16.to_be_bytes());
+
+        let (x, y) = srtm_file_coord(lat, lng, tile_origin(lat, lng), resolution);
+        let mut reader = std::io::Cursor::new(bytes);
+        assert_eq!(read_sample(&mut reader, resolution, x, y).unwrap(), 1234);
+    }
+}
+
+/// Validate the elevations read from tile bytes against the tile readers
+#[test]
+fn it_reads_elevation_from_tile_bytes() {
+    let resolution = Resolution::SRTM3;
+    let tile: Vec<u8> = (0..resolution.width() * resolution.height())
+        .flat_map(|i| (i as i16).to_be_bytes())
+        .collect();
+    for (lat, lng) in [
+        (49.5, -1.5),
+        (49.123, -1.987),
+        (49.0, -2.0),
+        (49.9999, -1.0001),
+    ] {
+        let (x, y) = srtm_file_coord(lat, lng, tile_origin(lat, lng), resolution);
+        let mut reader = std::io::Cursor::new(&tile);
+        let expect = read_sample(&mut reader, resolution, x, y).unwrap();
+        assert_eq!(elevation_from_slice(&tile, lat, lng).unwrap(), expect);
+    }
+}
+
+/// Validate the enumeration of the tiles covering a bounding box
+#[test]
+fn it_lists_the_tiles_of_a_bounding_box() {
+    let names = |from, to, boundary: TileBoundary| -> Vec<String> {
+        boundary
+            .bbox_origins(from, to)
+            .into_iter()
+            .map(origin_name)
+            .collect()
+    };
+
+    let expect = vec!["N50W002.hgt", "N50W001.hgt", "N49W002.hgt", "N49W001.hgt"];
+    assert_eq!(
+        names((49.5, -1.7), (50.1, -0.4), TileBoundary::NorthEast),
+        expect
+    );
+    assert_eq!(
+        names((49.5, -1.7), (50.1, -0.4), TileBoundary::NorthEast),
+        names((50.1, -1.7), (49.5, -0.4), TileBoundary::NorthEast)
+    );
+
+    // tile edges
+    assert_eq!(
+        names((49.0, -2.0), (50.0, -1.0), TileBoundary::NorthEast).len(),
+        4
+    );
+    assert_eq!(
+        names((49.0, -2.0), (50.0, -1.0), TileBoundary::SouthWest).len(),
+        4
+    );
+    assert_eq!(
+        names((49.0, -2.0), (50.0, -1.0), TileBoundary::SouthWest)[0],
+        "N49W003.hgt"
+    );
+
+    // antimeridian
+    let expect = vec!["N45E179.hgt", "N45W180.hgt"];
+    assert_eq!(
+        names((45.5, 179.5), (45.6, 180.0), TileBoundary::NorthEast),
+        expect
+    );
+    assert_eq!(
+        names((45.5, 179.5), (45.6, -179.5), TileBoundary::NorthEast),
+        expect
+    );
+    assert_eq!(
+        names((46.5, 178.5), (45.6, -178.5), TileBoundary::NorthEast).len(),
+        8
+    );
+    assert_eq!(
+        names((45.5, -1.0), (45.6, -2.0), TileBoundary::NorthEast).len(),
+        360
+    );
+}
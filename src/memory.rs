@@ -0,0 +1,160 @@
+//! Tiles fully loaded in memory.
+
+use crate::{
+    file::TileFile, has_data, origin_name, read_interpolated, read_sample, srtm_file_coord,
+    tile_files, Config, PixelConvention, Resolution, SrtmError, Tiles,
+};
+use anyhow::Result;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read, Seek, SeekFrom},
+};
+
+/// A **MemoryTiles** structure holds every tile of a [`Tiles`] in memory.
+///
+/// It is obtained with [`Tiles::into_memory`] and offers [`Tiles::elevation`] and
+/// [`Tiles::elevation_interpolated`] without any file access: this trades memory (25 MB per
+/// SRTM1 tile, 2.8 MB per SRTM3 tile) for the absence of syscalls per lookup. The configuration
+/// of the [`Tiles`] is kept: tile boundary, pixel convention, dimensions, strict mode and
+/// vertical datum. Heights are the raw samples, voids included, as returned by
+/// [`Tiles::elevation`]; the overrides (see [`Tiles::override_sample`]) and the audit sink are
+/// not kept.
+#[derive(Debug)]
+pub struct MemoryTiles {
+    config: Config,
+    tiles: HashMap<String, (Resolution, Vec<u8>)>,
+    corrupt: HashSet<String>,
+}
+
+impl Tiles {
+    /// Reads every .hgt tile of the archive or of the directories into memory.
+    ///
+    /// A tile present in several directories is read from the first one. In strict mode (see
+    /// [`TilesBuilder::strict`](crate::TilesBuilder::strict)), the tiles without data are not
+    /// loaded, and their lookups fail as they do with [`Tiles`].
+    ///
+    /// # Errors
+    ///
+    /// * IO errors while listing the directory or reading a tile.
+    /// * `SrtmError::ResolutionError` if a tile size is neither a SRTM one nor the configured
+    ///   dimensions (see [`TilesBuilder::dimensions`](crate::TilesBuilder::dimensions)).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path").into_memory()?;
+    /// println!("{} bytes loaded", tiles.bytes_loaded());
+    /// let altitude = tiles.elevation(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn into_memory(self) -> Result<MemoryTiles> {
+        let mut memory = MemoryTiles {
+            config: self.config,
+            tiles: HashMap::new(),
+            corrupt: HashSet::new(),
+        };
+        for (name, tile) in &self.archived {
+            let mut file = TileFile::open_archived(tile)?;
+            let mut bytes = vec![0; file.len() as usize];
+            file.seek(SeekFrom::Start(0))?;
+            file.read_exact(&mut bytes)?;
+            memory.insert(name, bytes)?;
+        }
+        for directory in &self.directories {
+            for (name, path) in tile_files(directory)? {
+                if !memory.tiles.contains_key(&name) && !memory.corrupt.contains(&name) {
+                    memory.insert(&name, std::fs::read(&path)?)?;
+                }
+            }
+        }
+        Ok(memory)
+    }
+}
+
+impl MemoryTiles {
+    /// Returns the total size, in bytes, of the tiles held in memory.
+    pub fn bytes_loaded(&self) -> u64 {
+        self.tiles
+            .values()
+            .map(|(_, bytes)| bytes.len() as u64)
+            .sum()
+    }
+
+    /// Returns the names of the tiles held in memory, sorted.
+    pub fn tile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.tiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns the vertical datum of the heights, as [`Tiles::vertical_datum`].
+    pub fn vertical_datum(&self) -> &str {
+        self.config.vertical_datum.as_deref().unwrap_or("EGM96")
+    }
+
+    /// Returns the elevation (height) from latitude and longitude.
+    ///
+    /// See [`Tiles::elevation`].
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MissingTile` if the tile containing the geoposition is not loaded.
+    /// * `SrtmError::CorruptTile` if the tile was rejected in strict mode.
+    pub fn elevation(&self, lat: f32, lng: f32) -> Result<i16> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |reader, resolution| {
+            let (x, y) = srtm_file_coord(lat, lng, origin, resolution);
+            read_sample(reader, resolution, x, y)
+        })
+    }
+
+    /// Returns the elevation (height) from latitude and longitude, linearly interpolated from
+    /// the four samples surrounding the geoposition.
+    ///
+    /// See [`Tiles::elevation_interpolated`], for the pixel convention too.
+    pub fn elevation_interpolated(&self, lat: f32, lng: f32) -> Result<f32> {
+        let (lat, lng) = match self.config.pixel {
+            PixelConvention::Point => (lat, lng),
+            PixelConvention::Area => {
+                // the samples are half a cell south-east of their grid point
+                let origin = self.config.boundary.origin(lat, lng);
+                let (spacing_lat, spacing_lng) =
+                    self.with_tile(origin, |_, resolution| Ok(resolution.spacing()))?;
+                (
+                    (lat as f64 + spacing_lat / 2.0) as f32,
+                    (lng as f64 - spacing_lng / 2.0) as f32,
+                )
+            }
+        };
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |reader, resolution| {
+            read_interpolated(reader, lat, lng, origin, resolution)
+        })
+    }
+
+    /// Keeps the bytes of a tile, after checking them in strict mode.
+    fn insert(&mut self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        let resolution = self.config.resolution(bytes.len() as u64)?;
+        if self.config.strict && !has_data(&mut Cursor::new(&bytes), resolution, self.config.void)?
+        {
+            self.corrupt.insert(name.to_string());
+        } else {
+            self.tiles.insert(name.to_string(), (resolution, bytes));
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with a reader over the tile whose south-west corner is `origin`.
+    fn with_tile<T, F>(&self, origin: (i32, i32), f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Cursor<&[u8]>, Resolution) -> Result<T>,
+    {
+        let name = origin_name(origin);
+        if self.corrupt.contains(&name) {
+            return Err(SrtmError::CorruptTile(name).into());
+        }
+        let (resolution, bytes) = self.tiles.get(&name).ok_or(SrtmError::MissingTile(name))?;
+        f(&mut Cursor::new(bytes), *resolution)
+    }
+}
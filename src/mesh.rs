@@ -0,0 +1,91 @@
+//! Terrain meshes streamed as triangle strips.
+
+use crate::{Rows, Tiles};
+use anyhow::Result;
+
+/// A **MeshStrip** is the triangle strip joining two consecutive rows of a region.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshStrip {
+    /// Index of the northern row of the strip, counted from the north of the region.
+    pub row: u32,
+    /// Vertices `[lng, lat, height]` in triangle strip order: alternately from the northern
+    /// and the southern row, from west to east. Voids are kept as is.
+    pub vertices: Vec<[f32; 3]>,
+}
+
+/// A **MeshStrips** iterator streams the triangle strips of a region, from north to south.
+///
+/// It is obtained with [`Tiles::mesh_strips`]. Only two rows of samples are held in memory at
+/// a time, whatever the size of the region.
+#[derive(Debug)]
+pub struct MeshStrips<'a> {
+    rows: Rows<'a>,
+    north: Option<Vec<i16>>,
+    row: u32,
+}
+
+impl Tiles {
+    /// Returns an iterator streaming the triangle strips of a region, from north to south.
+    ///
+    /// See [`Tiles::fill_region`] for the region selection: a region of `height` rows yields
+    /// `height - 1` strips of `2 × width` vertices, built from [`Tiles::rows`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// for strip in tiles.mesh_strips((50.0, -2.0), (49.0, -1.0))? {
+    ///     let strip = strip?;
+    ///     // upload strip.vertices, then drop them
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn mesh_strips(&self, from: (f32, f32), to: (f32, f32)) -> Result<MeshStrips<'_>> {
+        Ok(MeshStrips {
+            rows: self.rows(from, to)?,
+            north: None,
+            row: 0,
+        })
+    }
+}
+
+impl Iterator for MeshStrips<'_> {
+    type Item = Result<MeshStrip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let north = match self.north.take() {
+            Some(north) => north,
+            None => match self.rows.next()? {
+                Ok(north) => north,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        let south = match self.rows.next()? {
+            Ok(south) => south,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let (n, m) = (
+            (self.rows.resolution().width() - 1) as f64,
+            (self.rows.resolution().height() - 1) as f64,
+        );
+        let (lat0, lng0) = self.rows.north_west();
+        let lat = |row: u32| (lat0 as f64 - row as f64 / m) as f32;
+        let (north_lat, south_lat) = (lat(self.row), lat(self.row + 1));
+        let mut vertices = Vec::with_capacity(north.len() * 2);
+        for (col, (&n_height, &s_height)) in north.iter().zip(&south).enumerate() {
+            let lng = (lng0 as f64 + col as f64 / n) as f32;
+            vertices.push([lng, north_lat, n_height as f32]);
+            vertices.push([lng, south_lat, s_height as f32]);
+        }
+
+        let strip = MeshStrip {
+            row: self.row,
+            vertices,
+        };
+        self.north = Some(south);
+        self.row += 1;
+        Some(Ok(strip))
+    }
+}
@@ -0,0 +1,130 @@
+//! Orders of the tiles covering a bounding box.
+
+use crate::{origin_name, Tiles};
+
+/// Order of the tiles returned by [`Tiles::tiles_for_bbox_ordered`].
+///
+/// The curves are laid over the tiles of the box, counted in degrees from its north-west
+/// tile. Along a space-filling curve, tiles processed one after the other are neighbors, so
+/// that the row or column shared by consecutive tiles (and the tiles themselves, in a
+/// processing reading the neighbors of each tile) are still in the page cache.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TileOrder {
+    /// From north-west to south-east, row by row, as [`Tiles::tiles_for_bbox`].
+    #[default]
+    RowMajor,
+    /// Along a Morton (Z-order) curve: squares of 2×2, 4×4, ... tiles are contiguous, with
+    /// jumps between the squares.
+    Morton,
+    /// Along a Hilbert curve: squares of 2×2, 4×4, ... tiles are contiguous, and every tile
+    /// is a neighbor of the previous one, within the boxes whose sides are powers of two.
+    Hilbert,
+}
+
+impl TileOrder {
+    /// Returns the index along the curve of the tile at `(x, y)`, counted eastward and
+    /// southward from the north-west tile, over a square of `side` tiles (a power of two).
+    fn index(&self, x: u32, y: u32, side: u32) -> u64 {
+        match self {
+            TileOrder::RowMajor => y as u64 * side as u64 + x as u64,
+            TileOrder::Morton => spread(x) | spread(y) << 1,
+            TileOrder::Hilbert => hilbert(x, y, side),
+        }
+    }
+}
+
+/// Spreads the bits of `v` over the even bits of the result.
+fn spread(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | v << 16) & 0x0000_ffff_0000_ffff;
+    v = (v | v << 8) & 0x00ff_00ff_00ff_00ff;
+    v = (v | v << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | v << 2) & 0x3333_3333_3333_3333;
+    (v | v << 1) & 0x5555_5555_5555_5555
+}
+
+/// Returns the distance along the Hilbert curve of a square of `side` cells (a power of two)
+/// of the cell at `(x, y)`.
+fn hilbert(mut x: u32, mut y: u32, side: u32) -> u64 {
+    let mut d = 0u64;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        // rotates the quadrant, so that the curve is continuous
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+impl Tiles {
+    /// Returns the names of the tiles covering a bounding box, in the given order.
+    ///
+    /// These are the tiles of [`Tiles::tiles_for_bbox`], reordered: process the tiles of a
+    /// large region along a [`TileOrder::Hilbert`] curve so that neighboring tiles are
+    /// processed close in time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use easy_srtm::{TileOrder, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let names = tiles.tiles_for_bbox_ordered((48.5, -2.5), (49.5, -1.5), TileOrder::Hilbert);
+    /// assert_eq!(names, ["N49W003.hgt", "N48W003.hgt", "N48W002.hgt", "N49W002.hgt"]);
+    /// ```
+    pub fn tiles_for_bbox_ordered(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        order: TileOrder,
+    ) -> Vec<String> {
+        let origins = self.config.boundary.bbox_origins(from, to);
+        let width = origins
+            .iter()
+            .take_while(|origin| origin.0 == origins[0].0)
+            .count() as u32;
+        let height = origins.len() as u32 / width;
+        let side = width.max(height).next_power_of_two();
+
+        let mut indexed: Vec<(u64, (i32, i32))> = origins
+            .into_iter()
+            .enumerate()
+            .map(|(i, origin)| {
+                let (x, y) = (i as u32 % width, i as u32 / width);
+                (order.index(x, y, side), origin)
+            })
+            .collect();
+        indexed.sort_unstable_by_key(|&(index, _)| index);
+        indexed
+            .into_iter()
+            .map(|(_, origin)| origin_name(origin))
+            .collect()
+    }
+}
+
+/// Validate the indices of the cells of a 4×4 square along the curves.
+#[test]
+fn it_indexes_cells_along_the_curves() {
+    let indices = |order: TileOrder| -> Vec<u64> {
+        (0..4)
+            .flat_map(|y| (0..4).map(move |x| order.index(x, y, 4)))
+            .collect()
+    };
+    assert_eq!(indices(TileOrder::RowMajor), (0..16).collect::<Vec<_>>());
+    assert_eq!(
+        indices(TileOrder::Morton),
+        [0, 1, 4, 5, 2, 3, 6, 7, 8, 9, 12, 13, 10, 11, 14, 15]
+    );
+    assert_eq!(
+        indices(TileOrder::Hilbert),
+        [0, 1, 14, 15, 3, 2, 13, 12, 4, 7, 8, 11, 5, 6, 9, 10]
+    );
+}
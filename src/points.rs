@@ -0,0 +1,218 @@
+//! Elevations of sequences of geopositions.
+
+use crate::{origin_name, Resolution, SrtmError, Tiles};
+use anyhow::Result;
+use std::iter::FusedIterator;
+
+/// An **Elevations** iterator yields the elevations of a sequence of geopositions.
+///
+/// It is obtained with [`Tiles::elevations`]. Each item is the height of the next geoposition:
+///
+/// * `Ok(Some(height))` for a valid sample;
+/// * `Ok(None)` for a void (see [`TilesBuilder::void_value`](crate::TilesBuilder::void_value)),
+///   or a missing tile when [`TilesBuilder::allow_missing`](crate::TilesBuilder::allow_missing)
+///   is set;
+/// * `Err(_)` for any other error: a missing tile otherwise, a corrupted tile, IO errors like
+///   a permission denied...
+///
+/// Errors are fatal: the iterator yields the first one, then returns `None`. Collecting into a
+/// `Result<Vec<_>>` thus stops at the first error, and a partially consumed iterator never
+/// resumes past a failure.
+#[derive(Debug)]
+pub struct Elevations<'a, I> {
+    tiles: &'a Tiles,
+    points: I,
+    failed: bool,
+}
+
+/// A **ResolvedElevations** iterator yields the elevations of a sequence of geopositions,
+/// with the resolution of the tile each one is read from.
+///
+/// It is obtained with [`Tiles::elevations_with_resolution`]. Each item is
+/// `(lat, lng, height, resolution)`, where `height` is `None` for a void. As with
+/// [`Elevations`], the iterator yields the first error, then returns `None`. A missing tile
+/// is an error even with
+/// [`TilesBuilder::allow_missing`](crate::TilesBuilder::allow_missing), as it has no
+/// resolution to report.
+#[derive(Debug)]
+pub struct ResolvedElevations<'a, I> {
+    tiles: &'a Tiles,
+    points: I,
+    failed: bool,
+}
+
+impl Tiles {
+    /// Returns an iterator over the elevations of a sequence of geopositions (lat, lng).
+    ///
+    /// See [`Elevations`] for the items and the errors ending the iteration.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::builder("the_folder_path").allow_missing(true).build();
+    /// let track = [(49.1, -1.6), (49.2, -1.5), (49.3, -1.4)];
+    /// let heights = tiles.elevations(track).collect::<anyhow::Result<Vec<_>>>()?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevations<I>(&self, points: I) -> Elevations<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = (f32, f32)>,
+    {
+        Elevations {
+            tiles: self,
+            points: points.into_iter(),
+            failed: false,
+        }
+    }
+
+    /// Returns an iterator over the elevations of a sequence of geopositions (lat, lng), with
+    /// the resolution of their tiles.
+    ///
+    /// This suits directories mixing SRTM1 and SRTM3 tiles, where the accuracy of consecutive
+    /// heights may differ. See [`ResolvedElevations`] for the items and the errors ending the
+    /// iteration, and [`Tiles::elevations`] for the heights alone.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Resolution, Tiles};
+    /// let tiles = Tiles::builder("srtm1").directory("srtm3").build();
+    /// let track = [(49.1, -1.6), (49.2, -1.5), (49.3, -1.4)];
+    /// for point in tiles.elevations_with_resolution(track) {
+    ///     let (lat, lng, height, resolution) = point?;
+    ///     let accuracy_m = match resolution {
+    ///         Resolution::SRTM1 => 30.0,
+    ///         Resolution::SRTM3 => 90.0,
+    ///         Resolution::Dimensions { .. } => resolution.cell_size_m(lat).1,
+    ///     };
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevations_with_resolution<I>(&self, points: I) -> ResolvedElevations<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = (f32, f32)>,
+    {
+        ResolvedElevations {
+            tiles: self,
+            points: points.into_iter(),
+            failed: false,
+        }
+    }
+
+    /// Returns the elevations of a batch of geopositions (lat, lng), in the order of the
+    /// geopositions: `Ok(Some(height))` for a valid sample, `Ok(None)` for a void and `Err(_)`
+    /// for a missing tile or an IO error.
+    ///
+    /// This is the batch to use when the voids matter. Unlike [`Tiles::elevations`], errors
+    /// are not fatal: each geoposition gets its own result, and a missing tile is an error even
+    /// with [`TilesBuilder::allow_missing`](crate::TilesBuilder::allow_missing), so that it is
+    /// never mistaken for a void. The geopositions are read tile by tile, and the directories
+    /// are searched once for each missing tile.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let points = [(49.1, -1.6), (12.3, -45.6), (49.2, -1.5)];
+    /// for (point, height) in points.iter().zip(tiles.elevations_opt_batch(&points)) {
+    ///     match height {
+    ///         Ok(Some(height)) => println!("{:?}: {} m", point, height),
+    ///         Ok(None) => println!("{:?}: void", point),
+    ///         Err(e) => println!("{:?}: {}", point, e),
+    ///     }
+    /// }
+    /// ```
+    pub fn elevations_opt_batch(&self, points: &[(f32, f32)]) -> Vec<Result<Option<i16>>> {
+        let origins: Vec<(i32, i32)> = points
+            .iter()
+            .map(|&(lat, lng)| self.config.boundary.origin(lat, lng))
+            .collect();
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by_key(|&i| origins[i]);
+
+        let mut results: Vec<Option<Result<Option<i16>>>> = points.iter().map(|_| None).collect();
+        let mut missing: Option<(i32, i32)> = None;
+        for i in order {
+            let result = if missing == Some(origins[i]) {
+                Err(SrtmError::MissingTile(origin_name(origins[i])).into())
+            } else {
+                let (lat, lng) = points[i];
+                let result = self.elevation_opt(lat, lng);
+                if let Err(e) = &result {
+                    if matches!(e.downcast_ref(), Some(SrtmError::MissingTile(_))) {
+                        missing = Some(origins[i]);
+                    }
+                }
+                result
+            };
+            results[i] = Some(result);
+        }
+        results.into_iter().flatten().collect()
+    }
+}
+
+impl<I: Iterator<Item = (f32, f32)>> Iterator for Elevations<'_, I> {
+    type Item = Result<Option<i16>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let (lat, lng) = self.points.next()?;
+        match self.tiles.elevation_opt(lat, lng) {
+            Err(e)
+                if self.tiles.config.allow_missing
+                    && matches!(e.downcast_ref(), Some(SrtmError::MissingTile(_))) =>
+            {
+                Some(Ok(None))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+            result => Some(result),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            (0, self.points.size_hint().1)
+        }
+    }
+}
+
+impl<I: FusedIterator<Item = (f32, f32)>> FusedIterator for Elevations<'_, I> {}
+
+impl<I: Iterator<Item = (f32, f32)>> Iterator for ResolvedElevations<'_, I> {
+    type Item = Result<(f32, f32, Option<i16>, Resolution)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let (lat, lng) = self.points.next()?;
+        let result = self
+            .tiles
+            .elevation_with_resolution(lat, lng)
+            .map(|(height, resolution)| {
+                let height = Some(height).filter(|&height| !self.tiles.is_void_height(height));
+                (lat, lng, height, resolution)
+            });
+        self.failed = result.is_err();
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            (0, self.points.size_hint().1)
+        }
+    }
+}
+
+impl<I: FusedIterator<Item = (f32, f32)>> FusedIterator for ResolvedElevations<'_, I> {}
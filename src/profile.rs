@@ -0,0 +1,619 @@
+//! Elevation profiles along great circle paths.
+
+use crate::{
+    geo::{angular_distance, destination, haversine_m, intermediate, EARTH_RADIUS_M},
+    read_sample, srtm_file_cell, Interp, SrtmError, Tiles,
+};
+use anyhow::Result;
+
+/// Distance, in meters, up to which [`Tiles::horizon`] searches the terrain.
+const HORIZON_RANGE_M: f64 = 20_000.0;
+
+/// Interpolation of [`Tiles::horizon`], leaving the void corners out.
+const VOID_AWARE: Interp = Interp::BilinearVoidAware { search: 0 };
+
+/// Steepest climb or descent of the camera of [`Tiles::follow_terrain`], in meters per meter.
+const CAMERA_GRADE: f64 = 0.2;
+
+/// Maximum number of splits of a segment of [`Tiles::profile_adaptive`].
+const ADAPTIVE_DEPTH: u32 = 16;
+
+/// A **ProfilePoint** is a sample of an elevation profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfilePoint {
+    /// Latitude of the sample.
+    pub lat: f32,
+    /// Longitude of the sample.
+    pub lng: f32,
+    /// Great-circle distance, in meters, from the start of the profile.
+    pub distance_m: f32,
+    /// Interpolated elevation, in meters.
+    pub elevation: f32,
+}
+
+impl Tiles {
+    /// Returns the elevation profile between two geopositions, with `samples` points evenly
+    /// spread along the great circle path, both ends included.
+    ///
+    /// Elevations are interpolated (see [`Tiles::elevation_interpolated`]).
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `samples` is lower than 2.
+    /// * IO errors, if a tile along the path is missing.
+    pub fn profile(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        samples: usize,
+    ) -> Result<Vec<ProfilePoint>> {
+        if samples < 2 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "a profile needs at least 2 samples, not {}",
+                samples
+            ))
+            .into());
+        }
+        let length = haversine_m(from, to);
+        let fractions = (0..samples).map(|i| i as f64 / (samples - 1) as f64);
+        self.profile_at(from, to, length, fractions)
+    }
+
+    /// Returns the elevation profile between two geopositions, with a point every `spacing_m`
+    /// meters along the great circle path.
+    ///
+    /// The profile always ends at `to`, even if it is closer than `spacing_m` to the previous
+    /// point, so that the distances of the points are 0, `spacing_m`, `2 * spacing_m`, ... and
+    /// the path length.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `spacing_m` is not strictly positive.
+    /// * IO errors, if a tile along the path is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// for point in tiles.profile_spaced((49.1, -1.6), (49.2, -1.4), 30.0)? {
+    ///     println!("{} m: {} m", point.distance_m, point.elevation);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn profile_spaced(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        spacing_m: f32,
+    ) -> Result<Vec<ProfilePoint>> {
+        if spacing_m.is_nan() || spacing_m <= 0.0 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "profile spacing {} is not strictly positive",
+                spacing_m
+            ))
+            .into());
+        }
+        let length = haversine_m(from, to);
+        let steps = (length / spacing_m).ceil() as usize;
+        let fractions = (0..steps)
+            .map(|i| i as f64 * spacing_m as f64 / length as f64)
+            .chain(std::iter::once(1.0));
+        self.profile_at(from, to, length, fractions)
+    }
+
+    /// Returns the elevation profile between two geopositions, with points dense where the
+    /// terrain bends and sparse where it is straight.
+    ///
+    /// Starting from both ends, each segment of the great circle path is split at its middle
+    /// while the elevation of the middle differs by more than `max_err_m` meters from the
+    /// linear interpolation between the segment ends, down to segments of 1 / 65536 of the
+    /// path. Drawing straight lines between the points is then within about `max_err_m` of the
+    /// terrain. Features narrower than a segment whose middle happens to be on the line, such
+    /// as a spike between two samples, may still be missed.
+    ///
+    /// Elevations are interpolated (see [`Tiles::elevation_interpolated`]).
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `max_err_m` is not strictly positive.
+    /// * IO errors, if a tile along the path is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let chart = tiles.profile_adaptive((49.1, -1.6), (49.2, -1.4), 5.0)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn profile_adaptive(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        max_err_m: f32,
+    ) -> Result<Vec<ProfilePoint>> {
+        if max_err_m.is_nan() || max_err_m <= 0.0 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "profile error {} is not strictly positive",
+                max_err_m
+            ))
+            .into());
+        }
+        let length = haversine_m(from, to);
+        let first = self.profile_point(from, to, length, 0.0)?;
+        let last = self.profile_point(from, to, length, 1.0)?;
+        let mut points = vec![first];
+        self.subdivide(
+            (from, to, length),
+            ((0.0, first), (1.0, last)),
+            max_err_m,
+            0,
+            &mut points,
+        )?;
+        Ok(points)
+    }
+
+    /// Returns the interpolated elevation of a geoposition, lowered by the drop of the earth
+    /// curvature seen from the observer.
+    ///
+    /// The drop is `d² / (2R)`, for the great-circle distance d between the observer and the
+    /// geoposition on a sphere of radius R = 6371008.8 m (the mean earth radius): about 78 m at
+    /// 10 km, 7.8 km at 100 km. This is a visual approximation to place distant terrain at its
+    /// apparent height, not a geodetic height: it ignores the atmospheric refraction and the
+    /// ellipsoid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let apparent = tiles.elevation_curved(49.2, -1.4, (49.1, -1.6))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_curved(&self, lat: f32, lng: f32, observer: (f32, f32)) -> Result<f32> {
+        let distance = angular_distance(observer, (lat, lng)) * EARTH_RADIUS_M;
+        let drop = distance * distance / (2.0 * EARTH_RADIUS_M);
+        Ok((self.elevation_interpolated(lat, lng)? as f64 - drop) as f32)
+    }
+
+    /// Returns true if the terrain does not block the line of sight between two sites
+    /// `(lat, lng, height)`, where `height` is the height of the site above the ground, in
+    /// meters (e.g. the height of an antenna on its mast).
+    ///
+    /// The sightline is sampled along the great circle path with [`Tiles::profile_spaced`], a
+    /// point per cell of the tile of `from`. The terrain is raised by the earth bulge
+    /// `d1 * d2 / (2R)` between the sites, on a sphere of radius R = 6371008.8 m, without
+    /// atmospheric refraction. Grazing the terrain counts as visible.
+    ///
+    /// The points interpolated from a void are unknown terrain: they do not hide the sites, but
+    /// a sightline which is only clear over voids has no answer.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if the ground of a site is interpolated from a void, or if the
+    ///   terrain which does not block the sightline contains voids.
+    /// * IO errors, if a tile along the path is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let visible = tiles.has_line_of_sight((49.1, -1.6, 30.0), (49.2, -1.4, 2.0))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn has_line_of_sight(&self, from: (f32, f32, f32), to: (f32, f32, f32)) -> Result<bool> {
+        for (lat, lng, _) in [from, to] {
+            if self.is_void_interpolated(lat, lng)? {
+                return Err(SrtmError::NoData { lat, lng }.into());
+            }
+        }
+        let spacing = self.resolution_at(from.0, from.1)?.cell_size_m(from.0).1;
+        let profile = self.profile_spaced((from.0, from.1), (to.0, to.1), spacing)?;
+        let (first, last) = (profile[0], profile[profile.len() - 1]);
+        let start = (first.elevation + from.2) as f64;
+        let end = (last.elevation + to.2) as f64;
+        let length = last.distance_m as f64;
+        let mut void = None;
+        // sites at the same position have no point between them
+        let inner = profile.len().saturating_sub(2);
+        for point in profile.iter().skip(1).take(inner) {
+            if self.is_void_interpolated(point.lat, point.lng)? {
+                void.get_or_insert((point.lat, point.lng));
+                continue;
+            }
+            let d = point.distance_m as f64;
+            let sightline = start + (end - start) * d / length;
+            let bulge = d * (length - d) / (2.0 * EARTH_RADIUS_M);
+            if point.elevation as f64 + bulge > sightline {
+                return Ok(false);
+            }
+        }
+        match void {
+            Some((lat, lng)) => Err(SrtmError::NoData { lat, lng }.into()),
+            None => Ok(true),
+        }
+    }
+
+    /// Returns the inter-visibility of a set of sites `(lat, lng, height)`: entry `[i][j]` is
+    /// true if sites `i` and `j` see each other (see [`Tiles::has_line_of_sight`]).
+    ///
+    /// The matrix is symmetric and its diagonal is true. Each of the `N * (N - 1) / 2` pairs
+    /// is a sightline sampled independently, so that the cost grows with the square of the
+    /// number of sites and with the distances between them: about 330 samples for a 10 km
+    /// sightline in SRTM1.
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`Tiles::has_line_of_sight`], for the first failing pair of sites.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let towers = [(49.1, -1.6, 30.0), (49.2, -1.4, 40.0), (49.15, -1.2, 25.0)];
+    /// let matrix = tiles.visibility_matrix(&towers)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn visibility_matrix(&self, sites: &[(f32, f32, f32)]) -> Result<Vec<Vec<bool>>> {
+        let mut matrix = vec![vec![true; sites.len()]; sites.len()];
+        for i in 0..sites.len() {
+            for j in i + 1..sites.len() {
+                let visible = self.has_line_of_sight(sites[i], sites[j])?;
+                matrix[i][j] = visible;
+                matrix[j][i] = visible;
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Returns the radio coverage of a region from a transmitter `(lat, lng, height)`, as
+    /// `(width, height, clearances)`, where `height` is the height of the antenna above the
+    /// ground, in meters.
+    ///
+    /// The clearance of a sample is the smallest gap between the sightline from the antenna to
+    /// the ground at the sample and the terrain raised by the earth bulge (see
+    /// [`Tiles::has_line_of_sight`]), in radii of the first Fresnel zone at `freq_mhz`:
+    /// `r1 = sqrt(λ * d1 * d2 / (d1 + d2))` between the points at `d1` and `d2` from the ends.
+    /// 1 or more is a clear path, from 0 to 1 the terrain enters the Fresnel zone (0.6 is the
+    /// usual margin for a negligible loss), 0 is grazing and a negative clearance is an
+    /// obstructed path. Clearances are row-major, as in [`Tiles::grid`]; voids are NaN, as the
+    /// samples whose path to the antenna is only unobstructed over voids, and samples with no
+    /// terrain point between them and the antenna are infinite.
+    ///
+    /// Each sample is a sightline sampled independently: keep the regions small, or coarse.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `freq_mhz` is not strictly positive.
+    /// * `SrtmError::NoData` if the ground of the antenna is interpolated from a void.
+    /// * The errors of [`Tiles::grid`].
+    /// * IO errors, if a tile between the antenna and the region is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (width, height, clearances) =
+    ///     tiles.coverage_map((49.1, -1.6, 30.0), (49.2, -1.5), (49.1, -1.4), 868.0)?;
+    /// let covered = clearances.iter().filter(|&&c| c >= 0.6).count();
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn coverage_map(
+        &self,
+        tx: (f32, f32, f32),
+        from: (f32, f32),
+        to: (f32, f32),
+        freq_mhz: f32,
+    ) -> Result<(u32, u32, Vec<f32>)> {
+        if freq_mhz.is_nan() || freq_mhz <= 0.0 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "frequency {} MHz is not strictly positive",
+                freq_mhz
+            ))
+            .into());
+        }
+        if self.is_void_interpolated(tx.0, tx.1)? {
+            return Err(SrtmError::NoData {
+                lat: tx.0,
+                lng: tx.1,
+            }
+            .into());
+        }
+        let wavelength = 299.792458 / freq_mhz as f64;
+        let spacing = self.resolution_at(tx.0, tx.1)?.cell_size_m(tx.0).1;
+        let grid = self.grid(from, to)?;
+
+        let mut clearances = Vec::with_capacity(grid.data.len());
+        for row in 0..grid.height {
+            for col in 0..grid.width {
+                let sample = grid.data[(col + row * grid.width) as usize];
+                if self.is_void_height(sample) {
+                    clearances.push(f32::NAN);
+                    continue;
+                }
+                let profile =
+                    self.profile_spaced((tx.0, tx.1), grid.position(col, row), spacing)?;
+                let start = (profile[0].elevation + tx.2) as f64;
+                let end = sample as f64;
+                let length = profile[profile.len() - 1].distance_m as f64;
+                let (mut clearance, mut void) = (f64::INFINITY, false);
+                for point in profile.iter().skip(1).take(profile.len().saturating_sub(2)) {
+                    if self.is_void_interpolated(point.lat, point.lng)? {
+                        void = true;
+                        continue;
+                    }
+                    let d = point.distance_m as f64;
+                    let sightline = start + (end - start) * d / length;
+                    let bulge = d * (length - d) / (2.0 * EARTH_RADIUS_M);
+                    let radius = (wavelength * d * (length - d) / length).sqrt();
+                    clearance =
+                        clearance.min((sightline - point.elevation as f64 - bulge) / radius);
+                }
+                // an obstruction is known in spite of the voids, a clearance is not
+                if void && clearance >= 0.0 {
+                    clearance = f64::NAN;
+                }
+                clearances.push(clearance as f32);
+            }
+        }
+        Ok((grid.width, grid.height, clearances))
+    }
+
+    /// Returns the elevation angles of the horizon seen from an observer at `observer_height`
+    /// meters above the ground, in `azimuth_steps` directions.
+    ///
+    /// The direction `i` has the azimuth `i * 360 / azimuth_steps` degrees, clockwise from
+    /// the north (0 is the north, the next ones go east). Its angle, in degrees above the
+    /// horizontal, is the highest angle of the terrain along the great circle in this
+    /// direction, sampled a point per cell of the tile of the observer, up to 20 km. The terrain
+    /// is lowered by the earth curvature drop, as in [`Tiles::elevation_curved`], so that the
+    /// angles of a flat terrain are slightly negative. The heights are interpolated from the
+    /// valid corners only (see [`Interp::BilinearVoidAware`]), and points surrounded by voids
+    /// are skipped.
+    ///
+    /// Each direction interpolates the terrain at about 650 points in SRTM1 (220 in SRTM3).
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `azimuth_steps` is 0.
+    /// * IO errors, if a tile within 20 km is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // a direction every 2 degrees, from a rooftop 10 m above the ground
+    /// let horizon = tiles.horizon(49.1, -1.6, 10.0, 180)?;
+    /// let south = horizon[90];
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn horizon(
+        &self,
+        lat: f32,
+        lng: f32,
+        observer_height: f32,
+        azimuth_steps: usize,
+    ) -> Result<Vec<f32>> {
+        if azimuth_steps == 0 {
+            return Err(
+                SrtmError::InvalidArgument("a horizon needs an azimuth step".into()).into(),
+            );
+        }
+        let spacing = self.resolution_at(lat, lng)?.cell_size_m(lat).1 as f64;
+        let eye = self.elevation_interpolated(lat, lng)? as f64 + observer_height as f64;
+        let steps = (HORIZON_RANGE_M / spacing).ceil() as usize;
+        (0..azimuth_steps)
+            .map(|i| {
+                let azimuth = i as f64 * 360.0 / azimuth_steps as f64;
+                let mut angle = -90.0f64;
+                for step in 1..=steps {
+                    let d = step as f64 * spacing;
+                    let (lat, lng) = destination((lat, lng), azimuth, d);
+                    let height = match self.elevation_interp(lat, lng, VOID_AWARE) {
+                        Ok(height) => height as f64,
+                        Err(e) if matches!(e.downcast_ref(), Some(SrtmError::NoData { .. })) => {
+                            continue
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    let drop = d * d / (2.0 * EARTH_RADIUS_M);
+                    angle = angle.max((height - drop - eye).atan2(d).to_degrees());
+                }
+                Ok(angle as f32)
+            })
+            .collect()
+    }
+
+    /// Returns the track `(lat, lng, altitude)` of a camera flying `clearance_m` meters above
+    /// the terrain along a path, with `samples_per_segment` points evenly spread along each
+    /// great circle segment of the path, both ends included (the points shared by consecutive
+    /// segments are not repeated).
+    ///
+    /// The altitudes are smoothed so that the camera does not jerk over small bumps: the
+    /// climbs and descents are capped to a grade of 20 % (about 11°), the camera climbing
+    /// ahead of a hill and descending slowly behind it. The camera never flies lower than
+    /// `clearance_m` above the sampled terrain. The heights are interpolated from the valid
+    /// corners only (see [`Interp::BilinearVoidAware`]); over voids, the camera holds the
+    /// altitude of the previous point rather than diving.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the path has less than 2 points, or if
+    ///   `samples_per_segment` is lower than 2.
+    /// * `SrtmError::NoData` if every point of the path is surrounded by voids.
+    /// * IO errors, if a tile along the path is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let path = [(49.1, -1.6), (49.2, -1.5), (49.2, -1.3)];
+    /// for (lat, lng, altitude) in tiles.follow_terrain(&path, 150.0, 100)? {
+    ///     // move the camera
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn follow_terrain(
+        &self,
+        path: &[(f32, f32)],
+        clearance_m: f32,
+        samples_per_segment: usize,
+    ) -> Result<Vec<(f32, f32, f32)>> {
+        if path.len() < 2 || samples_per_segment < 2 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "a track needs at least 2 points and 2 samples per segment, not {} and {}",
+                path.len(),
+                samples_per_segment
+            ))
+            .into());
+        }
+        let mut positions = vec![path[0]];
+        let mut distances = vec![0.0];
+        let mut start = 0.0;
+        for segment in path.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            let length = haversine_m(from, to) as f64;
+            for i in 1..samples_per_segment {
+                let fraction = i as f64 / (samples_per_segment - 1) as f64;
+                positions.push(if i == samples_per_segment - 1 {
+                    to
+                } else {
+                    intermediate(from, to, fraction)
+                });
+                distances.push(start + fraction * length);
+            }
+            start += length;
+        }
+
+        let mut altitudes = Vec::with_capacity(positions.len());
+        let mut previous = None;
+        for &(lat, lng) in &positions {
+            match self.elevation_interp(lat, lng, VOID_AWARE) {
+                Ok(height) => previous = Some(height as f64 + clearance_m as f64),
+                Err(e) if matches!(e.downcast_ref(), Some(SrtmError::NoData { .. })) => {}
+                Err(e) => return Err(e),
+            }
+            altitudes.push(previous);
+        }
+        // voids before the first valid point hold its altitude
+        let Some(first) = altitudes.iter().flatten().next().copied() else {
+            let (lat, lng) = path[0];
+            return Err(SrtmError::NoData { lat, lng }.into());
+        };
+        let mut altitudes: Vec<f64> = altitudes
+            .into_iter()
+            .map(|altitude| altitude.unwrap_or(first))
+            .collect();
+
+        // raises the altitudes to the grade envelope: descents forward, climbs backward
+        for i in 1..altitudes.len() {
+            let floor = altitudes[i - 1] - CAMERA_GRADE * (distances[i] - distances[i - 1]);
+            altitudes[i] = altitudes[i].max(floor);
+        }
+        for i in (0..altitudes.len() - 1).rev() {
+            let floor = altitudes[i + 1] - CAMERA_GRADE * (distances[i + 1] - distances[i]);
+            altitudes[i] = altitudes[i].max(floor);
+        }
+        Ok(positions
+            .into_iter()
+            .zip(altitudes)
+            .map(|((lat, lng), altitude)| (lat, lng, altitude as f32))
+            .collect())
+    }
+
+    /// Returns true if one of the four samples the elevation of the geoposition is interpolated
+    /// from is a void (see [`Tiles::elevation_interpolated`]).
+    fn is_void_interpolated(&self, lat: f32, lng: f32) -> Result<bool> {
+        let (lat, lng) = self.interpolation_position(lat, lng)?;
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            let (x, y, _, _) = srtm_file_cell(lat, lng, origin, resolution);
+            for (cx, cy) in [(x, y), (x + 1, y), (x, y - 1), (x + 1, y - 1)] {
+                if self.is_void_height(read_sample(file, resolution, cx, cy)?) {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+    }
+
+    /// Returns the profile points at the given fractions of the path between two geopositions.
+    fn profile_at<I>(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        length: f32,
+        fractions: I,
+    ) -> Result<Vec<ProfilePoint>>
+    where
+        I: Iterator<Item = f64>,
+    {
+        fractions
+            .map(|fraction| self.profile_point(from, to, length, fraction))
+            .collect()
+    }
+
+    /// Returns the point at `fraction` of the great circle path of `length` meters.
+    fn profile_point(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        length: f32,
+        fraction: f64,
+    ) -> Result<ProfilePoint> {
+        let (lat, lng) = if fraction >= 1.0 {
+            to
+        } else {
+            intermediate(from, to, fraction)
+        };
+        Ok(ProfilePoint {
+            lat,
+            lng,
+            distance_m: (fraction * length as f64) as f32,
+            elevation: self.elevation_interpolated(lat, lng)?,
+        })
+    }
+
+    /// Appends to `points` the points of the adaptive profile strictly between the points at
+    /// `start` and `end` fractions of the path, then the point at `end`.
+    fn subdivide(
+        &self,
+        (from, to, length): ((f32, f32), (f32, f32), f32),
+        (start, end): ((f64, ProfilePoint), (f64, ProfilePoint)),
+        max_err_m: f32,
+        depth: u32,
+        points: &mut Vec<ProfilePoint>,
+    ) -> Result<()> {
+        if depth < ADAPTIVE_DEPTH {
+            let fraction = (start.0 + end.0) / 2.0;
+            let middle = self.profile_point(from, to, length, fraction)?;
+            let linear = (start.1.elevation + end.1.elevation) / 2.0;
+            if (middle.elevation - linear).abs() > max_err_m {
+                let path = (from, to, length);
+                self.subdivide(
+                    path,
+                    (start, (fraction, middle)),
+                    max_err_m,
+                    depth + 1,
+                    points,
+                )?;
+                self.subdivide(
+                    path,
+                    ((fraction, middle), end),
+                    max_err_m,
+                    depth + 1,
+                    points,
+                )?;
+                return Ok(());
+            }
+        }
+        points.push(end.1);
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Elevation lookups from projected coordinates.
+
+use crate::{SrtmError, Tiles};
+use anyhow::Result;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// WGS84 semi-major axis, in meters.
+const A: f64 = 6378137.0;
+/// WGS84 flattening.
+const F: f64 = 1.0 / 298.257223563;
+/// UTM scale factor on the central meridian.
+const K0: f64 = 0.9996;
+
+impl Tiles {
+    /// Returns the elevation (height) of projected coordinates.
+    ///
+    /// `x` and `y` are the easting and northing (or longitude and latitude for EPSG:4326) in the
+    /// coordinate reference system `epsg`. The projections are inverted with built-in formulas
+    /// rather than with the PROJ library, so no data files are needed, and the supported systems
+    /// are:
+    ///
+    /// - EPSG:4326, WGS84 geographic coordinates,
+    /// - EPSG:3857, Web Mercator (exact by definition),
+    /// - EPSG:32601 to EPSG:32660 and EPSG:32701 to EPSG:32760, WGS84 / UTM north and south
+    ///   zones (Krüger series, accurate to the millimeter within a zone).
+    ///
+    /// All of them are on the WGS84 datum, so no datum shift is applied.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::UnsupportedCrs` if the EPSG code is not supported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // WGS84 / UTM zone 30N
+    /// let altitude = tiles.elevation_projected(617_000.0, 5_440_000.0, 32630)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn elevation_projected(&self, x: f64, y: f64, epsg: u32) -> Result<i16> {
+        let (lat, lng) = to_wgs84(x, y, epsg)?;
+        self.elevation(lat as f32, lng as f32)
+    }
+}
+
+/// Returns the geographic coordinates (lat, lng) of projected coordinates.
+fn to_wgs84(x: f64, y: f64, epsg: u32) -> Result<(f64, f64), SrtmError> {
+    match epsg {
+        4326 => Ok((y, x)),
+        3857 => Ok((
+            (2.0 * (y / A).exp().atan() - FRAC_PI_2).to_degrees(),
+            (x / A).to_degrees(),
+        )),
+        32601..=32660 => Ok(utm_inverse(x, y, epsg - 32600, true)),
+        32701..=32760 => Ok(utm_inverse(x, y, epsg - 32700, false)),
+        _ => Err(SrtmError::UnsupportedCrs(epsg)),
+    }
+}
+
+/// Returns the third flattening n and the rectifying radius of WGS84.
+fn ellipsoid() -> (f64, f64) {
+    let n = F / (2.0 - F);
+    (n, A / (1.0 + n) * (1.0 + n * n / 4.0 + n.powi(4) / 64.0))
+}
+
+/// Inverts the UTM projection of the given zone (Krüger series).
+fn utm_inverse(easting: f64, northing: f64, zone: u32, north: bool) -> (f64, f64) {
+    let (n, radius) = ellipsoid();
+    let beta = [
+        n / 2.0 - 2.0 * n * n / 3.0 + 37.0 * n.powi(3) / 96.0,
+        n * n / 48.0 + n.powi(3) / 15.0,
+        17.0 * n.powi(3) / 480.0,
+    ];
+    let delta = [
+        2.0 * n - 2.0 * n * n / 3.0 - 2.0 * n.powi(3),
+        7.0 * n * n / 3.0 - 8.0 * n.powi(3) / 5.0,
+        56.0 * n.powi(3) / 15.0,
+    ];
+
+    let false_northing = if north { 0.0 } else { 10_000_000.0 };
+    let xi = (northing - false_northing) / (K0 * radius);
+    let eta = (easting - 500_000.0) / (K0 * radius);
+    let (mut xi1, mut eta1) = (xi, eta);
+    for (j, b) in (1..).zip(beta) {
+        let j = 2.0 * j as f64;
+        xi1 -= b * (j * xi).sin() * (j * eta).cosh();
+        eta1 -= b * (j * xi).cos() * (j * eta).sinh();
+    }
+    let chi = (xi1.sin() / eta1.cosh()).asin();
+    let mut lat = chi;
+    for (j, d) in (1..).zip(delta) {
+        lat += d * (2.0 * j as f64 * chi).sin();
+    }
+    let central = (zone as f64 * 6.0 - 183.0).to_radians();
+    let lng = central + eta1.sinh().atan2(xi1.cos());
+    let lng = (lng + PI).rem_euclid(2.0 * PI) - PI;
+    (lat.to_degrees(), lng.to_degrees())
+}
+
+/// Validate the inverse projections against reference positions
+#[test]
+fn it_inverts_projections() {
+    let check = |x, y, epsg, (lat, lng): (f64, f64)| {
+        let result = to_wgs84(x, y, epsg).unwrap();
+        assert!(
+            (result.0 - lat).abs() < 1e-7 && (result.1 - lng).abs() < 1e-7,
+            "failed for ({}, {}) in EPSG:{}: {:?}",
+            x,
+            y,
+            epsg,
+            result
+        );
+    };
+
+    check(-1.6, 49.1, 4326, (49.1, -1.6));
+    check(0.0, 0.0, 3857, (0.0, 0.0));
+    // Web Mercator bounds (85.0511287798° at y = π·a)
+    check(PI * A, PI * A, 3857, (85.0511287798066, 180.0));
+    // central meridians at the equator
+    check(500_000.0, 0.0, 32631, (0.0, 3.0));
+    check(500_000.0, 10_000_000.0, 32719, (0.0, -69.0));
+    // WGS84 meridian arc length at 45° is 4984944.378 m
+    check(500_000.0, 4_984_944.378 * K0, 32631, (45.0, 3.0));
+    check(
+        500_000.0,
+        10_000_000.0 - 4_984_944.378 * K0,
+        32731,
+        (-45.0, 3.0),
+    );
+
+    assert!(to_wgs84(0.0, 0.0, 2154).is_err());
+}
@@ -0,0 +1,758 @@
+//! Reading of the samples of a region spanning one or more tiles.
+
+use crate::{origin_name, wrap_lng, Interp, Resolution, SrtmError, TileBoundary, Tiles};
+use anyhow::Result;
+use byteorder::{BigEndian, ByteOrder};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A **Grid** holds the samples of a region, on the native grid of its tiles.
+///
+/// Samples are stored row-major: the first row is the northernmost one and each row goes from
+/// west to east. The sample at (`col`, `row`) is `data[col + row * width]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    /// Number of samples in a row.
+    pub width: u32,
+    /// Number of rows.
+    pub height: u32,
+    /// Resolution of the tiles the samples were read from.
+    pub resolution: Resolution,
+    /// Geoposition (lat, lng) of the first (north-west) sample.
+    pub north_west: (f32, f32),
+    /// Row-major samples.
+    pub data: Vec<i16>,
+}
+
+impl Grid {
+    /// Returns the sample at the given column and row, if inside the grid.
+    pub fn get(&self, col: u32, row: u32) -> Option<i16> {
+        if col < self.width && row < self.height {
+            Some(self.data[(col + row * self.width) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the geoposition (lat, lng) of the sample at the given column and row.
+    ///
+    /// Longitudes are wrapped into [-180, 180) for grids crossing the antimeridian.
+    pub fn position(&self, col: u32, row: u32) -> (f32, f32) {
+        let (n, m) = (
+            (self.resolution.width() - 1) as f64,
+            (self.resolution.height() - 1) as f64,
+        );
+        let (lat, lng) = self.north_west;
+        (
+            (lat as f64 - row as f64 / m) as f32,
+            wrap_lng((lng as f64 + col as f64 / n) as f32),
+        )
+    }
+}
+
+/// A **Rows** iterator streams the rows of a region, from north to south.
+///
+/// It is obtained with [`Tiles::rows`]. Each item holds one row of samples from west to east,
+/// so that only a single row is held in memory at a time.
+#[derive(Debug)]
+pub struct Rows<'a> {
+    tiles: &'a Tiles,
+    region: Region,
+    row: u32,
+}
+
+impl Rows<'_> {
+    /// Returns the number of samples in a row.
+    pub fn width(&self) -> u32 {
+        self.region.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> u32 {
+        self.region.height
+    }
+
+    /// Returns the resolution of the tiles the samples are read from.
+    pub fn resolution(&self) -> Resolution {
+        self.region.resolution
+    }
+
+    /// Returns the geoposition (lat, lng) of the first (north-west) sample.
+    pub fn north_west(&self) -> (f32, f32) {
+        self.region.north_west()
+    }
+}
+
+impl Iterator for Rows<'_> {
+    type Item = Result<Vec<i16>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row >= self.region.height {
+            return None;
+        }
+        let mut out = vec![0; self.region.width as usize];
+        let result = self.tiles.read_region_row(&self.region, self.row, &mut out);
+        self.row += 1;
+        Some(result.map(|_| out))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.region.height - self.row) as usize;
+        (len, Some(len))
+    }
+}
+
+/// A rectangular region on the global sample grid of a resolution.
+///
+/// This grid has `n = side - 1` samples per degree: rows are counted southward from the north
+/// pole and columns eastward from the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Region {
+    pub(crate) resolution: Resolution,
+    /// Global index of the northernmost row.
+    pub(crate) row: i64,
+    /// Global index of the westernmost column.
+    pub(crate) col: i64,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+/// A run of consecutive samples of a region row, read from a single tile row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Segment {
+    /// South-west corner of the tile.
+    pub(crate) origin: (i32, i32),
+    /// Tile row.
+    pub(crate) y: u32,
+    /// First tile column.
+    pub(crate) x: u32,
+    /// Number of samples.
+    pub(crate) len: u32,
+}
+
+impl Region {
+    /// Returns the region whose corners (lat, lng) are the samples nearest to `from` and `to`.
+    ///
+    /// `from` and `to` are two opposite corners, with the latitudes in any order. The region spans
+    /// eastward from the longitude of `from` to the one of `to`, across the antimeridian if
+    /// `from` is east of `to`.
+    pub(crate) fn new(from: (f32, f32), to: (f32, f32), resolution: Resolution) -> Self {
+        let (n, m) = (
+            (resolution.width() - 1) as f64,
+            (resolution.height() - 1) as f64,
+        );
+        let (north, south) = (from.0.max(to.0) as f64, from.0.min(to.0) as f64);
+        let (west, mut east) = (from.1 as f64, to.1 as f64);
+        if west > east {
+            east += 360.0;
+        }
+        let row = ((90.0 - north) * m).round() as i64;
+        let col = ((west + 180.0) * n).round() as i64;
+        Self {
+            resolution,
+            row,
+            col,
+            width: (((east + 180.0) * n).round() as i64 - col + 1) as u32,
+            height: (((90.0 - south) * m).round() as i64 - row + 1) as u32,
+        }
+    }
+
+    /// Returns the number of samples of the region.
+    pub(crate) fn len(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Returns the geoposition (lat, lng) of the north-west sample.
+    pub(crate) fn north_west(&self) -> (f32, f32) {
+        let (n, m) = (
+            (self.resolution.width() - 1) as f64,
+            (self.resolution.height() - 1) as f64,
+        );
+        (
+            (90.0 - self.row as f64 / m) as f32,
+            (self.col as f64 / n - 180.0) as f32,
+        )
+    }
+
+    /// Returns the segments covering the given row of the region, from west to east.
+    ///
+    /// Samples on tile edges are read from the tile selected by `boundary`.
+    pub(crate) fn segments(&self, row: u32, boundary: TileBoundary) -> Vec<Segment> {
+        let (n, m) = (
+            (self.resolution.width() - 1) as i64,
+            (self.resolution.height() - 1) as i64,
+        );
+        let (degree, last): (fn(i64, i64) -> i64, i64) = match boundary {
+            TileBoundary::NorthEast => (|i, n| i.div_euclid(n), n - 1),
+            TileBoundary::SouthWest => (|i, n| (i + n - 1).div_euclid(n) - 1, n),
+        };
+
+        // rows are counted northward from the south pole within tiles
+        let north = 180 * m - (self.row + row as i64);
+        let lat = degree(north, m);
+        let y = (m - (north - lat * m)) as u32;
+
+        let mut segments = Vec::new();
+        let (mut col, end) = (self.col, self.col + self.width as i64);
+        while col < end {
+            let lng = degree(col, n);
+            let x = col - lng * n;
+            let len = (last + 1 - x).min(end - col);
+            let origin = ((lat - 90) as i32, (lng.rem_euclid(360) - 180) as i32);
+            segments.push(Segment {
+                origin,
+                y,
+                x: x as u32,
+                len: len as u32,
+            });
+            col += len;
+        }
+        segments
+    }
+}
+
+/// Writes the samples of a grid row by row into a new file, as big-endian samples.
+fn write_samples(path: &Path, grid: &Grid) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut bytes = vec![0; grid.width as usize * 2];
+    for row in grid.data.chunks(grid.width as usize) {
+        BigEndian::write_i16_into(row, &mut bytes);
+        writer.write_all(&bytes)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the consecutive samples of a tile row starting at (x, y) into `out`.
+pub(crate) fn read_row_segment<R: Read + Seek>(
+    reader: &mut R,
+    resolution: Resolution,
+    x: u32,
+    y: u32,
+    out: &mut [i16],
+) -> Result<()> {
+    let index = x as u64 + y as u64 * resolution.width() as u64;
+    reader.seek(SeekFrom::Start(index * 2))?;
+    let mut bytes = vec![0; out.len() * 2];
+    reader.read_exact(&mut bytes)?;
+    BigEndian::read_i16_into(&bytes, out);
+    Ok(())
+}
+
+impl Tiles {
+    /// Returns the region between two corners on the grid of the tile containing `from`.
+    pub(crate) fn region(&self, from: (f32, f32), to: (f32, f32)) -> Result<Region> {
+        let origin = self.config.boundary.origin(from.0, from.1);
+        let resolution = self.with_tile(origin, |_, resolution| Ok(resolution))?;
+        Ok(Region::new(from, to, resolution))
+    }
+
+    /// Reads the given row of a region into `out`, whose length is the region width.
+    pub(crate) fn read_region_row(&self, region: &Region, row: u32, out: &mut [i16]) -> Result<()> {
+        let mut offset = 0;
+        for segment in region.segments(row, self.config.boundary) {
+            let out = &mut out[offset..offset + segment.len as usize];
+            let result = self.with_tile(segment.origin, |file, resolution| {
+                if resolution != region.resolution {
+                    return Err(SrtmError::MixedResolution.into());
+                }
+                if self.config.read_buffer == 0 {
+                    return read_row_segment(file, resolution, segment.x, segment.y, &mut *out);
+                }
+                let index = segment.x as u64 + segment.y as u64 * resolution.width() as u64;
+                let mut bytes = vec![0; out.len() * 2];
+                file.read_ahead(index * 2, &mut bytes, self.config.read_buffer)?;
+                BigEndian::read_i16_into(&bytes, out);
+                Ok(())
+            });
+            match result {
+                Err(e)
+                    if self.config.allow_missing
+                        && matches!(e.downcast_ref(), Some(SrtmError::MissingTile(_))) =>
+                {
+                    out.fill(self.config.void)
+                }
+                result => result
+                    .map_err(|e| e.context(format!("reading {}", origin_name(segment.origin))))?,
+            }
+            offset += segment.len as usize;
+        }
+        Ok(())
+    }
+
+    /// Writes the samples of a region into a caller-provided buffer and returns the grid
+    /// dimensions `(width, height)`.
+    ///
+    /// The region is the rectangle between two opposite corners `from` and `to` (lat, lng),
+    /// snapped to the nearest samples, on the grid of the tile containing `from`. It spans
+    /// eastward from `from`, across the antimeridian if `from` is east of `to`. All the tiles it
+    /// spans must have the same resolution.
+    ///
+    /// Samples are written row-major: `out[col + row * width]` is the sample of the column `col`
+    /// (from west to east) and the row `row` (from north to south). Only the first
+    /// `width * height` values of `out` are written.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::BufferTooSmall` if `out` holds less than `width * height` samples.
+    /// * `SrtmError::MixedResolution` if the region spans tiles of different resolutions.
+    /// * IO errors, if a tile is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let mut out = vec![0; 1201 * 1201];
+    /// let (width, height) = tiles.fill_region((50.0, -2.0), (49.0, -1.0), &mut out)?;
+    /// assert_eq!((width, height), (1201, 1201));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn fill_region(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        out: &mut [i16],
+    ) -> Result<(u32, u32)> {
+        let region = self.region(from, to)?;
+        if out.len() < region.len() {
+            return Err(SrtmError::BufferTooSmall {
+                needed: region.len(),
+                len: out.len(),
+            }
+            .into());
+        }
+        out.chunks_mut(region.width as usize)
+            .take(region.height as usize)
+            .enumerate()
+            .try_for_each(|(row, out)| self.read_region_row(&region, row as u32, out))?;
+        Ok((region.width, region.height))
+    }
+
+    /// Returns an iterator streaming the rows of a region, from north to south.
+    ///
+    /// See [`Tiles::fill_region`] for the region selection. Rows are read lazily, which
+    /// keeps the memory bounded for large regions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// for row in tiles.rows((50.0, -2.0), (49.0, -1.0))? {
+    ///     let row = row?;
+    ///     // ...
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn rows(&self, from: (f32, f32), to: (f32, f32)) -> Result<Rows<'_>> {
+        Ok(Rows {
+            tiles: self,
+            region: self.region(from, to)?,
+            row: 0,
+        })
+    }
+
+    /// Calls `f` with the geoposition and the height `(lat, lng, height)` of every sample of a
+    /// region, until it returns `ControlFlow::Break`.
+    ///
+    /// This is the push counterpart of [`Tiles::rows`], which it reads the region from: samples
+    /// are visited row-major over the whole region, from north to south and from west to east
+    /// in each row, across the tile edges. Only a single row is held in memory at a time. See
+    /// [`Tiles::fill_region`] for the region selection.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// use std::ops::ControlFlow;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let mut highest = i16::MIN;
+    /// tiles.for_each_in_bbox((50.0, -2.0), (49.0, -1.0), |_, _, height| {
+    ///     highest = highest.max(height);
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn for_each_in_bbox<F>(&self, from: (f32, f32), to: (f32, f32), mut f: F) -> Result<()>
+    where
+        F: FnMut(f32, f32, i16) -> ControlFlow<()>,
+    {
+        let rows = self.rows(from, to)?;
+        let (n, m) = (
+            (rows.resolution().width() - 1) as f64,
+            (rows.resolution().height() - 1) as f64,
+        );
+        let (lat0, lng0) = rows.north_west();
+        for (row, samples) in rows.enumerate() {
+            let lat = (lat0 as f64 - row as f64 / m) as f32;
+            for (col, height) in samples?.into_iter().enumerate() {
+                let lng = (lng0 as f64 + col as f64 / n) as f32;
+                if f(lat, lng, height).is_break() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all the samples of the tile containing the geoposition as a [`Grid`].
+    ///
+    /// The grid is `width × height` samples (3601 × 3601 for SRTM1, 1201 × 1201 for SRTM3),
+    /// edges included.
+    pub fn load_tile(&self, lat: f32, lng: f32) -> Result<Grid> {
+        let origin = self.config.boundary.origin(lat, lng);
+        self.with_tile(origin, |file, resolution| {
+            let (width, height) = (resolution.width(), resolution.height());
+            let mut data = vec![0; (width * height) as usize];
+            read_row_segment(file, resolution, 0, 0, &mut data)?;
+            Ok(Grid {
+                width,
+                height,
+                resolution,
+                north_west: ((origin.0 + 1) as f32, origin.1 as f32),
+                data,
+            })
+        })
+    }
+
+    /// Writes the tile containing the geoposition to the directory `out`, as a standard `.hgt`
+    /// file named after its south-west corner (e.g. `N49W002.hgt`).
+    ///
+    /// The tile is loaded with [`Tiles::load_tile`], whatever it is stored in (a file or an
+    /// archive), and written row by row as big-endian samples. It is written to a `.partial`
+    /// file first, then renamed: an existing file of the same name is replaced, even when `out`
+    /// is the directory the tile is read from, and no truncated file is left on errors.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the tile is not a SRTM1 or SRTM3 tile.
+    /// * `SrtmError::ResolutionError` if the samples do not have the size of the tile
+    ///   resolution (see [`Resolution::file_size`]).
+    /// * IO errors, if the tile is missing or the file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// use std::path::Path;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// tiles.write_hgt(49.1, -1.6, Path::new("the_extract_path"))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn write_hgt(&self, lat: f32, lng: f32, out: &Path) -> Result<()> {
+        let grid = self.load_tile(lat, lng)?;
+        if !matches!(grid.resolution, Resolution::SRTM1 | Resolution::SRTM3) {
+            return Err(SrtmError::InvalidArgument(format!(
+                "{:?} is not a SRTM1 or SRTM3 resolution",
+                grid.resolution
+            ))
+            .into());
+        }
+        if grid.data.len() as u64 * 2 != grid.resolution.file_size() {
+            return Err(SrtmError::ResolutionError.into());
+        }
+        let path = out.join(origin_name(self.config.boundary.origin(lat, lng)));
+        let partial = path.with_extension("hgt.partial");
+        if let Err(e) = write_samples(&partial, &grid) {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+        std::fs::rename(&partial, &path)?;
+        Ok(())
+    }
+
+    /// Splits the tile containing the geoposition into blocks of `window × window` samples,
+    /// and returns an iterator over each block `(col0, row0, data)`.
+    ///
+    /// `col0` and `row0` are the tile column and row of the north-west sample of the block, and
+    /// `data` holds its samples row-major (see [`Grid`]). Blocks are yielded row-major: from west
+    /// to east, then from north to south.
+    ///
+    /// Consecutive blocks share `overlap` columns (or rows), so that meshes built from each
+    /// block join seamlessly: blocks start every `window - overlap` samples. The blocks of the
+    /// last column and row are truncated to the tile edge and may be smaller than `window`.
+    ///
+    /// The tile is loaded once (see [`Tiles::load_tile`]), blocks are copied from it lazily.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if `window` is 0 or not greater than `overlap`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// for (col0, row0, block) in tiles.windows(49.1, -1.6, 257, 1)? {
+    ///     // upload block
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn windows(
+        &self,
+        lat: f32,
+        lng: f32,
+        window: u32,
+        overlap: u32,
+    ) -> Result<impl Iterator<Item = (u32, u32, Vec<i16>)>> {
+        if window == 0 || window <= overlap {
+            return Err(SrtmError::InvalidArgument(format!(
+                "window {} must be greater than overlap {}",
+                window, overlap
+            ))
+            .into());
+        }
+        let grid = self.load_tile(lat, lng)?;
+        let starts = |side: u32| -> Vec<u32> {
+            (0..side)
+                .step_by((window - overlap) as usize)
+                .take_while(|&start| start == 0 || start + overlap < side)
+                .collect()
+        };
+        let (columns, rows) = (starts(grid.width), starts(grid.height));
+        let blocks = rows
+            .into_iter()
+            .flat_map(move |row0| columns.clone().into_iter().map(move |col0| (col0, row0)));
+
+        Ok(blocks.map(move |(col0, row0)| {
+            let (width, height) = (
+                window.min(grid.width - col0),
+                window.min(grid.height - row0),
+            );
+            let mut data = Vec::with_capacity((width * height) as usize);
+            for row in row0..row0 + height {
+                let start = (col0 + row * grid.width) as usize;
+                data.extend_from_slice(&grid.data[start..start + width as usize]);
+            }
+            (col0, row0, data)
+        }))
+    }
+
+    /// Returns the samples of a region as a [`Grid`].
+    ///
+    /// See [`Tiles::fill_region`] for the region selection.
+    pub fn grid(&self, from: (f32, f32), to: (f32, f32)) -> Result<Grid> {
+        let rows = self.rows(from, to)?;
+        let (width, height) = (rows.width(), rows.height());
+        let (resolution, north_west) = (rows.resolution(), rows.north_west());
+        let mut data = Vec::with_capacity(width as usize * height as usize);
+        for row in rows {
+            data.extend(row?);
+        }
+        Ok(Grid {
+            width,
+            height,
+            resolution,
+            north_west,
+            data,
+        })
+    }
+
+    /// Returns the elevations of an arbitrary grid of `rows × cols` geopositions, interpolated
+    /// with the given method.
+    ///
+    /// The geoposition of the row `i` and the column `j` is
+    /// `(origin.0 + i * d_lat, origin.1 + j * d_lng)`: use a negative `d_lat` for rows from north
+    /// to south, like [`Grid`]. Elevations are row-major: `out[j + i * cols]`. The grid does not
+    /// need to be aligned on the samples of the tiles, nor to have their spacing. Tiles are
+    /// opened once and kept for the whole scan.
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`Tiles::elevation_interp`], for the first failing geoposition.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Interp, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// // 0.01° cells from the north-west corner (49.9, -1.9)
+    /// let raster = tiles.sample_grid((49.9, -1.9), -0.01, 0.01, 80, 80, Interp::Bilinear)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn sample_grid(
+        &self,
+        origin: (f32, f32),
+        d_lat: f32,
+        d_lng: f32,
+        rows: usize,
+        cols: usize,
+        interp: Interp,
+    ) -> Result<Vec<f32>> {
+        let mut out = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            let lat = (origin.0 as f64 + i as f64 * d_lat as f64) as f32;
+            for j in 0..cols {
+                let lng = (origin.1 as f64 + j as f64 * d_lng as f64) as f32;
+                out.push(self.elevation_interp(lat, lng, interp)?);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Returns the samples of a region as a [`Grid`], unless `cancel` is set meanwhile.
+    ///
+    /// Same as [`Tiles::grid`], for interactive applications which may drop the request of a
+    /// large region. The flag is checked before reading each row, so that the read stops at most
+    /// one row (a few kilobytes of samples) after the flag is set.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::Cancelled` if `cancel` was set before the last row was read.
+    /// * The errors of [`Tiles::fill_region`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// use std::sync::atomic::AtomicBool;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let cancel = AtomicBool::new(false);
+    /// // share `cancel` with the UI thread, which sets it when the user navigates away
+    /// let grid = tiles.grid_cancellable((50.0, -2.0), (49.0, -1.0), &cancel)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn grid_cancellable(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        cancel: &AtomicBool,
+    ) -> Result<Grid> {
+        let region = self.region(from, to)?;
+        let mut data = vec![0; region.len()];
+        for (row, out) in data.chunks_mut(region.width as usize).enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(SrtmError::Cancelled.into());
+            }
+            self.read_region_row(&region, row as u32, out)?;
+        }
+        Ok(Grid {
+            width: region.width,
+            height: region.height,
+            resolution: region.resolution,
+            north_west: region.north_west(),
+            data,
+        })
+    }
+
+    /// Returns the samples of a region which are not voids, keyed by their grid coordinates
+    /// `(col, row)`.
+    ///
+    /// The samples are those of [`Tiles::grid`] for the same corners, streamed with
+    /// [`Tiles::rows`] so that the dense grid is never held. Regions mostly made of voids, like
+    /// the sea around islands, take much less memory this way. The grid dimensions are not
+    /// returned: they are those of [`Tiles::rows`] for the same corners, and the positions of
+    /// the samples are given by [`Grid::position`] on them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let land = tiles.region_sparse((49.8, -2.0), (49.0, -1.0))?;
+    /// if let Some(height) = land.get(&(120, 300)) {
+    ///     // ...
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn region_sparse(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+    ) -> Result<HashMap<(u32, u32), i16>> {
+        let mut samples = HashMap::new();
+        for (row, heights) in self.rows(from, to)?.enumerate() {
+            for (col, height) in heights?.into_iter().enumerate() {
+                if !self.is_void_height(height) {
+                    samples.insert((col as u32, row as u32), height);
+                }
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Returns the differences of the samples of a region between these tiles and `other`,
+    /// i.e. `self - other`, as a [`Grid`].
+    ///
+    /// See [`Tiles::fill_region`] for the region selection, on both tiles. A sample which is a
+    /// void in either input, with the void values of each one, is a void of these tiles in the
+    /// output (see [`crate::TilesBuilder::void_value`]). Differences beyond the range of `i16`
+    /// are saturated.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::MixedResolution` if the region has different resolutions in both tiles.
+    /// * The errors of [`Tiles::grid`], on both tiles.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let (srtm, newer) = (Tiles::new("the_folder_path"), Tiles::new("the_newer_folder_path"));
+    /// let changes = newer.diff(&srtm, (49.5, -1.5), (49.0, -1.0))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn diff(&self, other: &Tiles, from: (f32, f32), to: (f32, f32)) -> Result<Grid> {
+        let grid = self.grid(from, to)?;
+        let base = other.grid(from, to)?;
+        if grid.resolution != base.resolution {
+            return Err(SrtmError::MixedResolution.into());
+        }
+        let data = grid
+            .data
+            .iter()
+            .zip(&base.data)
+            .map(|(&height, &base)| {
+                if self.is_void_height(height) || other.is_void_height(base) {
+                    self.config.void
+                } else {
+                    height.saturating_sub(base)
+                }
+            })
+            .collect();
+        Ok(Grid { data, ..grid })
+    }
+}
+
+/// Validate the split of region rows into tile segments
+#[test]
+fn it_splits_region_rows_into_tile_segments() {
+    let region = Region::new((50.5, -1.5), (49.5, -0.5), Resolution::SRTM3);
+    assert_eq!((region.width, region.height), (1201, 1201));
+
+    let segments = region.segments(0, TileBoundary::NorthEast);
+    let expect = vec![
+        Segment {
+            origin: (50, -2),
+            y: 600,
+            x: 600,
+            len: 600,
+        },
+        Segment {
+            origin: (50, -1),
+            y: 600,
+            x: 0,
+            len: 601,
+        },
+    ];
+    assert_eq!(segments, expect);
+
+    // the row on the N50 southern edge
+    let segments = region.segments(600, TileBoundary::NorthEast);
+    assert!(segments.iter().all(|s| s.origin.0 == 50 && s.y == 1200));
+    let segments = region.segments(600, TileBoundary::SouthWest);
+    assert!(segments.iter().all(|s| s.origin.0 == 49 && s.y == 0));
+    assert_eq!(segments[0].len, 601);
+    assert_eq!(segments[0].x + segments[0].len - 1, 1200);
+
+    // antimeridian
+    let region = Region::new((0.5, 179.5), (0.5, 180.0), Resolution::SRTM3);
+    let segments = region.segments(0, TileBoundary::NorthEast);
+    assert_eq!(segments[0].origin, (0, 179));
+    assert_eq!(segments[1].origin, (0, -180));
+}
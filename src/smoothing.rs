@@ -0,0 +1,121 @@
+//! Smoothing of region grids.
+
+use crate::{Grid, SrtmError, Tiles};
+use anyhow::Result;
+
+/// A **Kernel** is the filter applied by [`Tiles::grid_smoothed`], with its size: the side of
+/// the square window centered on each sample, an odd number of samples (3 for 3×3, 5 for 5×5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// Mean of the window.
+    Mean(u32),
+    /// Median of the window, which removes isolated spikes while keeping sharp breaks.
+    Median(u32),
+    /// Gaussian weighted mean of the window, with a standard deviation of
+    /// `0.3 * (size / 2 - 1) + 0.8` samples (about 0.8 for 3×3, 1.1 for 5×5).
+    Gaussian(u32),
+}
+
+impl Kernel {
+    /// Returns the size of the window.
+    pub fn size(&self) -> u32 {
+        match *self {
+            Kernel::Mean(size) | Kernel::Median(size) | Kernel::Gaussian(size) => size,
+        }
+    }
+
+    /// Returns the weight of a sample at (`dx`, `dy`) from the center, of a mean kernel.
+    fn weight(&self, dx: i64, dy: i64) -> f64 {
+        match *self {
+            Kernel::Gaussian(size) => {
+                let sigma = 0.3 * ((size / 2) as f64 - 1.0) + 0.8;
+                (-((dx * dx + dy * dy) as f64) / (2.0 * sigma * sigma)).exp()
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+impl Tiles {
+    /// Returns the samples of a region as a [`Grid`], smoothed with a kernel.
+    ///
+    /// See [`Tiles::fill_region`] for the region selection. Each sample is replaced by the
+    /// filtered heights of the window centered on it, rounded to the nearest meter. Voids are
+    /// left out of the windows; a sample whose window holds only voids is a void. Windows are
+    /// truncated at the region edges: samples outside the region are not read.
+    ///
+    /// Smoothing removes the speckle of the radar data, for cleaner slopes or hillshades, but
+    /// it also biases the relief: peaks and ridges are lowered, valleys are raised, more so
+    /// with larger kernels.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the kernel size is not an odd number greater than 1.
+    /// * The errors of [`Tiles::grid`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::{Kernel, Tiles};
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let grid = tiles.grid_smoothed((50.0, -2.0), (49.0, -1.0), Kernel::Gaussian(5))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn grid_smoothed(&self, from: (f32, f32), to: (f32, f32), kernel: Kernel) -> Result<Grid> {
+        let size = kernel.size();
+        if size < 3 || size % 2 == 0 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "kernel size {} is not an odd number greater than 1",
+                size
+            ))
+            .into());
+        }
+        let grid = self.grid(from, to)?;
+        let radius = (size / 2) as i64;
+        let (width, height) = (grid.width as i64, grid.height as i64);
+
+        let mut data = Vec::with_capacity(grid.data.len());
+        let mut window = Vec::with_capacity((size * size) as usize);
+        for row in 0..height {
+            for col in 0..width {
+                window.clear();
+                for y in (row - radius).max(0)..=(row + radius).min(height - 1) {
+                    for x in (col - radius).max(0)..=(col + radius).min(width - 1) {
+                        let sample = grid.data[(x + y * width) as usize];
+                        if !self.is_void_height(sample) {
+                            window.push((sample, kernel.weight(x - col, y - row)));
+                        }
+                    }
+                }
+                data.push(self.filter(kernel, &mut window));
+            }
+        }
+        Ok(Grid { data, ..grid })
+    }
+
+    /// Returns the filtered height of the valid samples of a window, with their weights.
+    fn filter(&self, kernel: Kernel, window: &mut [(i16, f64)]) -> i16 {
+        if window.is_empty() {
+            return self.config.void;
+        }
+        match kernel {
+            Kernel::Median(_) => {
+                window.sort_unstable_by_key(|&(sample, _)| sample);
+                let middle = window.len() / 2;
+                if window.len() % 2 != 0 {
+                    window[middle].0
+                } else {
+                    ((window[middle - 1].0 as f64 + window[middle].0 as f64) / 2.0).round() as i16
+                }
+            }
+            _ => {
+                let total: f64 = window.iter().map(|&(_, weight)| weight).sum();
+                let sum: f64 = window
+                    .iter()
+                    .map(|&(sample, weight)| sample as f64 * weight)
+                    .sum();
+                (sum / total).round() as i16
+            }
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Statistics of the samples of an area.
+
+use crate::{geo::EARTH_RADIUS_M, origin_name, SrtmError, Tiles};
+use anyhow::Result;
+use std::ops::ControlFlow;
+
+/// **Stats** summarize the heights of the samples of an area, voids excluded.
+///
+/// They are obtained with [`Tiles::corridor_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Lowest height.
+    pub min: i16,
+    /// Highest height.
+    pub max: i16,
+    /// Mean height.
+    pub mean: f32,
+    /// Number of samples.
+    pub count: u64,
+}
+
+/// Cumulative histogram of the heights of a tile, voids excluded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Histogram {
+    /// Lowest height of the tile.
+    min: i16,
+    /// Number of heights lower than `min + i`, for each `i` up to the range of the heights,
+    /// the last one being the number of heights.
+    below: Vec<u32>,
+}
+
+impl Histogram {
+    /// Counts the heights.
+    fn new(heights: &[i16]) -> Self {
+        let (Some(&min), Some(&max)) = (heights.iter().min(), heights.iter().max()) else {
+            return Histogram {
+                min: 0,
+                below: vec![0],
+            };
+        };
+        let mut below = vec![0; (max as i32 - min as i32) as usize + 2];
+        for &height in heights {
+            below[(height as i32 - min as i32) as usize + 1] += 1;
+        }
+        for i in 1..below.len() {
+            below[i] += below[i - 1];
+        }
+        Histogram { min, below }
+    }
+
+    /// Returns the number of heights.
+    fn len(&self) -> u32 {
+        self.below[self.below.len() - 1]
+    }
+
+    /// Returns the number of heights lower than `height`.
+    fn below(&self, height: i16) -> u32 {
+        let i = (height as i32 - self.min as i32).clamp(0, self.below.len() as i32 - 1);
+        self.below[i as usize]
+    }
+}
+
+impl Tiles {
+    /// Returns the mean elevation of the samples inside a polygon.
+    ///
+    /// The polygon is given by its vertices (lat, lng), closed from the last vertex back to the
+    /// first one. It is assumed simple (its edges do not cross) and small enough for its edges
+    /// to be drawn as straight lines in latitude and longitude, not crossing the antimeridian.
+    /// The samples of its bounding box are read with [`Tiles::rows`] and kept when their
+    /// position is inside the polygon (ray casting test). Voids are excluded from the mean.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the polygon has less than 3 vertices.
+    /// * `SrtmError::NoData` if no valid sample lies inside the polygon.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let district = [(49.1, -1.6), (49.3, -1.5), (49.2, -1.1)];
+    /// let mean = tiles.polygon_mean(&district)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn polygon_mean(&self, vertices: &[(f32, f32)]) -> Result<f32> {
+        if vertices.len() < 3 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "a polygon needs at least 3 vertices, not {}",
+                vertices.len()
+            ))
+            .into());
+        }
+        let (mut north, mut south) = (f32::MIN, f32::MAX);
+        let (mut east, mut west) = (f32::MIN, f32::MAX);
+        for &(lat, lng) in vertices {
+            (north, south) = (north.max(lat), south.min(lat));
+            (east, west) = (east.max(lng), west.min(lng));
+        }
+
+        let rows = self.rows((north, west), (south, east))?;
+        let (n, m) = (
+            (rows.resolution().width() - 1) as f64,
+            (rows.resolution().height() - 1) as f64,
+        );
+        let (lat0, lng0) = rows.north_west();
+        let (mut sum, mut count) = (0.0, 0u64);
+        for (row, samples) in rows.enumerate() {
+            let lat = (lat0 as f64 - row as f64 / m) as f32;
+            for (col, height) in samples?.into_iter().enumerate() {
+                let lng = (lng0 as f64 + col as f64 / n) as f32;
+                if !self.is_void_height(height) && contains(vertices, lat, lng) {
+                    sum += height as f64;
+                    count += 1;
+                }
+            }
+        }
+        if count == 0 {
+            let (lat, lng) = ((north + south) / 2.0, (east + west) / 2.0);
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+        Ok((sum / count as f64) as f32)
+    }
+
+    /// Returns the position (lat, lng) and the height of the highest sample of a region, voids
+    /// excluded, e.g. to label the local summit.
+    ///
+    /// The samples are those of [`Tiles::for_each_in_bbox`], streamed from north-west to
+    /// south-east: among equal highest samples, the first one in this order is returned. The
+    /// position is the one of the sample, on the grid of the tiles.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if every sample of the region is a void.
+    /// * The errors of [`Tiles::for_each_in_bbox`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (lat, lng, height) = tiles.highest((49.2, -1.7), (49.1, -1.6))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn highest(&self, from: (f32, f32), to: (f32, f32)) -> Result<(f32, f32, i16)> {
+        self.extreme(from, to, |height, best| height > best)
+    }
+
+    /// Returns the position (lat, lng) and the height of the lowest sample of a region, voids
+    /// excluded.
+    ///
+    /// See [`Tiles::highest`].
+    pub fn lowest(&self, from: (f32, f32), to: (f32, f32)) -> Result<(f32, f32, i16)> {
+        self.extreme(from, to, |height, best| height < best)
+    }
+
+    /// Returns the statistics of the heights of the samples within `buffer_m` meters of a
+    /// polyline, e.g. along the centerline of a road or a railway.
+    ///
+    /// The polyline is given by its points (lat, lng); a single point gives a disc. The
+    /// samples of the bounding box of the corridor are read with [`Tiles::for_each_in_bbox`]
+    /// and kept when their distance to the nearest segment is at most `buffer_m`. The segments
+    /// are drawn as straight lines in latitude and longitude, and the distances are computed
+    /// on the local plane of each sample: the corridor is exact enough for buffers of a few
+    /// kilometers, away from the poles and the antimeridian. Voids are excluded.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArgument` if the polyline is empty, or if `buffer_m` is negative.
+    /// * `SrtmError::NoData` if no valid sample lies within the corridor.
+    /// * The errors of [`Tiles::for_each_in_bbox`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let road = [(49.1, -1.6), (49.15, -1.5), (49.2, -1.5)];
+    /// let stats = tiles.corridor_stats(&road, 250.0)?;
+    /// println!("from {} m to {} m", stats.min, stats.max);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn corridor_stats(&self, centerline: &[(f32, f32)], buffer_m: f32) -> Result<Stats> {
+        if centerline.is_empty() || buffer_m.is_nan() || buffer_m < 0.0 {
+            return Err(SrtmError::InvalidArgument(format!(
+                "a corridor needs a point and a positive buffer, not {} points and {} m",
+                centerline.len(),
+                buffer_m
+            ))
+            .into());
+        }
+        let segments: Vec<((f32, f32), (f32, f32))> = if centerline.len() == 1 {
+            vec![(centerline[0], centerline[0])]
+        } else {
+            centerline
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .collect()
+        };
+
+        let buffer_deg = (buffer_m as f64 / EARTH_RADIUS_M).to_degrees();
+        let (mut north, mut south) = (f64::MIN, f64::MAX);
+        let (mut east, mut west) = (f64::MIN, f64::MAX);
+        for &(lat, lng) in centerline {
+            (north, south) = (north.max(lat as f64), south.min(lat as f64));
+            (east, west) = (east.max(lng as f64), west.min(lng as f64));
+        }
+        let (north, south) = (
+            (north + buffer_deg).min(90.0),
+            (south - buffer_deg).max(-90.0),
+        );
+        let widest = north.abs().max(south.abs()).to_radians().cos().max(1e-6);
+        let (east, west) = (east + buffer_deg / widest, west - buffer_deg / widest);
+
+        let (mut min, mut max) = (i16::MAX, i16::MIN);
+        let (mut sum, mut count) = (0f64, 0u64);
+        self.for_each_in_bbox(
+            (north as f32, west as f32),
+            (south as f32, east as f32),
+            |lat, lng, height| {
+                if !self.is_void_height(height)
+                    && segments
+                        .iter()
+                        .any(|&(a, b)| segment_distance_m((lat, lng), a, b) <= buffer_m as f64)
+                {
+                    (min, max) = (min.min(height), max.max(height));
+                    sum += height as f64;
+                    count += 1;
+                }
+                ControlFlow::Continue(())
+            },
+        )?;
+        if count == 0 {
+            let (lat, lng) = centerline[0];
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+        Ok(Stats {
+            min,
+            max,
+            mean: (sum / count as f64) as f32,
+            count,
+        })
+    }
+
+    /// Returns the fraction of the samples of the tile containing the geoposition which are
+    /// lower than its elevation, voids excluded: 0 at the lowest sample of the tile, 0.85 for a
+    /// viewpoint higher than 85 % of the tile.
+    ///
+    /// The elevation is the one of [`Tiles::elevation`]. The first rank in a tile reads the
+    /// whole tile (see [`Tiles::load_tile`]) into a histogram of its heights, kept until
+    /// [`Tiles::refresh`]: the following ranks in this tile are a lookup. The overrides of
+    /// [`Tiles::override_sample`] are not counted in the histogram.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if the elevation is a void, or if every sample of the tile is.
+    /// * The errors of [`Tiles::elevation`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let rank = tiles.percentile_rank(49.1, -1.6)?;
+    /// println!("higher than {:.0} % of the area", rank * 100.0);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn percentile_rank(&self, lat: f32, lng: f32) -> Result<f32> {
+        let height = self.elevation(lat, lng)?;
+        if self.is_void_height(height) {
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+        let name = origin_name(self.config.boundary.origin(lat, lng));
+        if !self.histograms.borrow().contains_key(&name) {
+            let mut heights = self.load_tile(lat, lng)?.data;
+            heights.retain(|&height| !self.is_void_height(height));
+            let histogram = Histogram::new(&heights);
+            self.histograms.borrow_mut().insert(name.clone(), histogram);
+        }
+        let histograms = self.histograms.borrow();
+        let histogram = &histograms[&name];
+        if histogram.len() == 0 {
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+        Ok(histogram.below(height) as f32 / histogram.len() as f32)
+    }
+
+    /// Returns the first sample of a region which is not a void and `better` than all the
+    /// others.
+    fn extreme(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        better: fn(i16, i16) -> bool,
+    ) -> Result<(f32, f32, i16)> {
+        let mut best: Option<(f32, f32, i16)> = None;
+        self.for_each_in_bbox(from, to, |lat, lng, height| {
+            let improves = match best {
+                Some((_, _, b)) => better(height, b),
+                None => true,
+            };
+            if !self.is_void_height(height) && improves {
+                best = Some((lat, lng, height));
+            }
+            ControlFlow::Continue(())
+        })?;
+        best.ok_or_else(|| {
+            let (lat, lng) = ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+            SrtmError::NoData { lat, lng }.into()
+        })
+    }
+}
+
+/// Returns true if the geoposition is inside the polygon, with the ray casting test.
+fn contains(vertices: &[(f32, f32)], lat: f32, lng: f32) -> bool {
+    let mut inside = false;
+    let mut previous = vertices[vertices.len() - 1];
+    for &vertex in vertices {
+        let ((lat1, lng1), (lat2, lng2)) = (previous, vertex);
+        if (lat1 > lat) != (lat2 > lat) && lng < lng1 + (lat - lat1) / (lat2 - lat1) * (lng2 - lng1)
+        {
+            inside = !inside;
+        }
+        previous = vertex;
+    }
+    inside
+}
+
+/// Returns the distance, in meters, between a geoposition and a segment, on the plane
+/// tangent to the earth at the geoposition.
+fn segment_distance_m(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f64 {
+    let meters_per_degree = EARTH_RADIUS_M.to_radians();
+    let scale = (point.0 as f64).to_radians().cos() * meters_per_degree;
+    let plane = |(lat, lng): (f32, f32)| {
+        (
+            (lng as f64 - point.1 as f64) * scale,
+            (lat as f64 - point.0 as f64) * meters_per_degree,
+        )
+    };
+    let ((ax, ay), (bx, by)) = (plane(a), plane(b));
+    let (dx, dy) = (bx - ax, by - ay);
+    let length2 = dx * dx + dy * dy;
+    let t = if length2 > 0.0 {
+        (-(ax * dx + ay * dy) / length2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (ax + t * dx).hypot(ay + t * dy)
+}
+
+/// Validate the distances to segments, inside and beyond their ends
+#[test]
+fn it_computes_distances_to_segments() {
+    let (a, b) = ((0.0, 0.0), (0.0, 1.0));
+    let degree = EARTH_RADIUS_M.to_radians();
+    assert!((segment_distance_m((0.01, 0.5), a, b) - 0.01 * degree).abs() < 1.0);
+    assert!((segment_distance_m((0.0, 1.01), a, b) - 0.01 * degree).abs() < 1.0);
+    assert!((segment_distance_m((-0.01, -0.01), a, b) - 0.01 * degree * 2f64.sqrt()).abs() < 1.0);
+    assert!((segment_distance_m((0.01, 0.0), a, a) - 0.01 * degree).abs() < 1.0);
+    // a degree of longitude is half as long at 60°
+    let (a, b) = ((59.0, 0.0), (61.0, 0.0));
+    assert!((segment_distance_m((60.0, 0.02), a, b) - 0.01 * degree).abs() < 1.0);
+}
+
+/// Validate the counts of the heights below a height
+#[test]
+fn it_counts_the_heights_below_a_height() {
+    let histogram = Histogram::new(&[5, 3, 3, 8, -2]);
+    assert_eq!(histogram.len(), 5);
+    assert_eq!(histogram.below(-10), 0);
+    assert_eq!(histogram.below(-2), 0);
+    assert_eq!(histogram.below(3), 1);
+    assert_eq!(histogram.below(4), 3);
+    assert_eq!(histogram.below(8), 4);
+    assert_eq!(histogram.below(100), 5);
+    assert_eq!(Histogram::new(&[]).len(), 0);
+}
+
+/// Validate the point in polygon test
+#[test]
+fn it_tests_points_in_polygons() {
+    let square = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+    assert!(contains(&square, 0.5, 0.5));
+    assert!(!contains(&square, 1.5, 0.5));
+    assert!(!contains(&square, 0.5, -0.5));
+
+    // concave "U" shape, open to the north
+    let u = [
+        (0.0, 0.0),
+        (0.0, 3.0),
+        (3.0, 3.0),
+        (3.0, 2.0),
+        (1.0, 2.0),
+        (1.0, 1.0),
+        (3.0, 1.0),
+        (3.0, 0.0),
+    ];
+    assert!(contains(&u, 2.0, 0.5));
+    assert!(contains(&u, 0.5, 1.5));
+    assert!(!contains(&u, 2.0, 1.5));
+}
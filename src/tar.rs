@@ -0,0 +1,154 @@
+//! Tiles stored in a tar archive.
+
+use crate::{file::ArchivedTile, hgt_name, SrtmError, Tiles, TilesBuilder};
+use anyhow::Result;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Size of the tar blocks.
+const BLOCK: u64 = 512;
+
+impl Tiles {
+    /// Returns a Tiles object reading the .hgt files stored in a tar archive.
+    ///
+    /// The entries of the archive are listed once, here: lookups then seek into the archive to
+    /// the samples of the tile, without extracting it. This random access needs the archive to
+    /// be an uncompressed, seekable file (not a `.tar.gz`, nor a pipe). Tiles are found in any
+    /// folder of the archive, by file name (see [`Tiles::new`]); the resolution of a tile comes
+    /// from the size of its entry. POSIX (ustar), GNU long names and PAX paths are supported.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::InvalidArchive` if the file is not a tar archive.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::from_tar("srtm.tar")?;
+    /// let altitude = tiles.elevation(49.1, -1.6)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_tar<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut tiles = TilesBuilder {
+            directories: Vec::new(),
+            config: Default::default(),
+        }
+        .build();
+        tiles.archived = tar_entries(path)?;
+        Ok(tiles)
+    }
+}
+
+/// Lists the .hgt entries of a tar archive.
+///
+/// The first entry of a tile name wins, as the first directory does.
+fn tar_entries(path: &Path) -> Result<HashMap<String, ArchivedTile>> {
+    let invalid = || SrtmError::InvalidArchive(path.to_path_buf());
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut entries = HashMap::new();
+    let mut long_name: Option<PathBuf> = None;
+    let mut header = [0u8; BLOCK as usize];
+    let mut position = 0;
+
+    while position + BLOCK <= len {
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut header)?;
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        if octal(&header[148..156]).ok_or_else(invalid)? != checksum(&header) {
+            return Err(invalid().into());
+        }
+        let size = octal(&header[124..136]).ok_or_else(invalid)?;
+        let data = position + BLOCK;
+        if data + size > len {
+            return Err(invalid().into());
+        }
+
+        match header[156] {
+            b'L' => long_name = Some(PathBuf::from(text(&read_data(&mut file, size)?))),
+            b'x' => long_name = pax_path(&read_data(&mut file, size)?),
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or_else(|| header_path(&header));
+                if let Some(name) = hgt_name(&name) {
+                    entries.entry(name).or_insert(ArchivedTile {
+                        path: path.to_path_buf(),
+                        start: data,
+                        len: size,
+                    });
+                }
+            }
+            _ => long_name = None,
+        }
+        position = data + size.div_ceil(BLOCK) * BLOCK;
+    }
+    Ok(entries)
+}
+
+/// Returns the path of an entry from its ustar header (name, and prefix if any).
+fn header_path(header: &[u8]) -> PathBuf {
+    let name = text(&header[0..100]);
+    if &header[257..262] == b"ustar" && header[345] != 0 {
+        Path::new(&text(&header[345..500])).join(name)
+    } else {
+        PathBuf::from(name)
+    }
+}
+
+/// Returns the path record of PAX extended header data, if any.
+fn pax_path(data: &[u8]) -> Option<PathBuf> {
+    // records are "<length> <key>=<value>\n"
+    text(data)
+        .lines()
+        .rev()
+        .find_map(|record| record.split_once(' ')?.1.strip_prefix("path="))
+        .map(PathBuf::from)
+}
+
+/// Reads the data of the current entry.
+fn read_data(file: &mut File, size: u64) -> Result<Vec<u8>> {
+    let mut data = vec![0; size as usize];
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Returns the text of a nul-terminated header field.
+fn text(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+/// Parses an octal header field, terminated by a nul or a space.
+fn octal(field: &[u8]) -> Option<u64> {
+    let digits = text(field);
+    u64::from_str_radix(digits.trim_matches(|c| c == ' ' || c == '\0'), 8).ok()
+}
+
+/// Returns the checksum of a header: the sum of its bytes, with the checksum field as spaces.
+fn checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { b' ' } else { b } as u64)
+        .sum()
+}
+
+/// Validate the parsing of the header fields
+#[test]
+fn it_parses_tar_header_fields() {
+    assert_eq!(octal(b"00000001750\0"), Some(1000));
+    assert_eq!(octal(b"   1750 "), Some(1000));
+    assert_eq!(octal(b"\0\0\0"), None);
+    assert_eq!(
+        pax_path(b"30 mtime=1350244992.023960108\n24 path=srtm/N49W002.hgt\n"),
+        Some(PathBuf::from("srtm/N49W002.hgt"))
+    );
+    assert_eq!(pax_path(b"30 mtime=1350244992.023960108\n"), None);
+}
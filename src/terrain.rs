@@ -0,0 +1,288 @@
+//! Terrain shape around a geoposition.
+
+use crate::{region::Region, SrtmError, Tiles};
+use anyhow::Result;
+
+/// A **Terrain** describes the terrain shape around a sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Terrain {
+    /// Height of the sample, in meters.
+    pub height: f32,
+    /// Slope, in degrees from the horizontal.
+    pub slope_deg: f32,
+    /// Direction the slope faces (downhill), in degrees clockwise from the north, in [0, 360).
+    /// Flat terrain faces the north (0).
+    pub aspect_deg: f32,
+    /// Unit normal vector of the terrain, as (east, north, up) components.
+    pub normal: [f32; 3],
+}
+
+impl Tiles {
+    /// Returns the height, slope, aspect and normal of the terrain at the sample nearest to the
+    /// geoposition, computed from a single read of its 3×3 neighborhood.
+    ///
+    /// The gradient is computed with Horn's method, from the ground spacing between the samples
+    /// at this latitude (see [`crate::Resolution::cell_size_m`]). Neighbors across a tile edge
+    /// are read from the neighboring tile, which must have the same resolution.
+    ///
+    /// # Errors
+    ///
+    /// * `SrtmError::NoData` if a sample of the neighborhood is a void.
+    /// * `SrtmError::MissingTile` if a tile of the neighborhood is missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let terrain = tiles.terrain(49.1, -1.6)?;
+    /// println!("{:.1}° facing {:.0}°", terrain.slope_deg, terrain.aspect_deg);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn terrain(&self, lat: f32, lng: f32) -> Result<Terrain> {
+        let center = self.region((lat, lng), (lat, lng))?;
+        let window = self.neighborhood(&center)?;
+        if window.iter().any(|&height| self.is_void_height(height)) {
+            return Err(SrtmError::NoData { lat, lng }.into());
+        }
+
+        let z = window.map(|height| height as f64);
+        let (dx, dy) = center.resolution.cell_size_m(center.north_west().0);
+        let east = ((z[2] + 2.0 * z[5] + z[8]) - (z[0] + 2.0 * z[3] + z[6])) / (8.0 * dx as f64);
+        let north = ((z[0] + 2.0 * z[1] + z[2]) - (z[6] + 2.0 * z[7] + z[8])) / (8.0 * dy as f64);
+
+        let slope = east.hypot(north);
+        let aspect = if slope == 0.0 {
+            0.0
+        } else {
+            (-east).atan2(-north).to_degrees().rem_euclid(360.0)
+        };
+        let length = (slope * slope + 1.0).sqrt();
+        Ok(Terrain {
+            height: z[4] as f32,
+            slope_deg: slope.atan().to_degrees() as f32,
+            aspect_deg: aspect as f32,
+            normal: [
+                (-east / length) as f32,
+                (-north / length) as f32,
+                (1.0 / length) as f32,
+            ],
+        })
+    }
+
+    /// Returns the path following the steepest descent (or ascent) from a geoposition.
+    ///
+    /// The path starts at the sample nearest to `start` and repeatedly steps to the lowest
+    /// (with `descending`, or else the highest) of its 8 neighbors, giving a flow line downhill
+    /// or a ridge line uphill. It stops at a pit (or a peak), where no neighbor is strictly
+    /// lower (or higher), or after `max_steps` steps. Voids are never stepped on, and a path
+    /// starting on a void stops there. Each point is the position (lat, lng) of a sample with
+    /// its height; neighbors across a tile edge are read from the neighboring tile.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let drainage = tiles.steepest_path((49.1, -1.6), true, 1000)?;
+    /// let (lat, lng, height) = drainage[drainage.len() - 1];
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn steepest_path(
+        &self,
+        start: (f32, f32),
+        descending: bool,
+        max_steps: usize,
+    ) -> Result<Vec<(f32, f32, i16)>> {
+        let mut center = self.region(start, start)?;
+        let mut path = Vec::new();
+        loop {
+            let window = self.neighborhood(&center)?;
+            let (lat, lng) = center.north_west();
+            path.push((lat, lng, window[4]));
+            if path.len() > max_steps || self.is_void_height(window[4]) {
+                return Ok(path);
+            }
+
+            let steeper = |a: i16, b: i16| if descending { a < b } else { a > b };
+            let mut next = None;
+            let mut best = window[4];
+            for (i, &height) in window.iter().enumerate() {
+                if !self.is_void_height(height) && steeper(height, best) {
+                    (next, best) = (Some(i), height);
+                }
+            }
+            match next {
+                Some(i) => {
+                    center.row += i as i64 / 3 - 1;
+                    center.col += i as i64 % 3 - 1;
+                }
+                None => return Ok(path),
+            }
+        }
+    }
+
+    /// Returns the samples of a region where the slope is at most `max_slope_deg`, as
+    /// `(width, height, mask)`.
+    ///
+    /// See [`Tiles::fill_region`] for the region selection: the mask is row-major, as the
+    /// samples of [`Tiles::grid`]. The slope of each sample is computed as by
+    /// [`Tiles::terrain`]: Horn's method over its 3×3 neighborhood, with the ground spacing of
+    /// the samples at the latitude of its row. The neighborhoods of the samples on the region
+    /// edges are read beyond the region, from the neighboring tiles if needed. A sample whose
+    /// neighborhood holds a void is not buildable.
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`Tiles::fill_region`], for the region extended by one sample on each
+    ///   side.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// let (width, height, mask) = tiles.buildable_mask((49.2, -1.7), (49.1, -1.6), 8.0)?;
+    /// let buildable = mask.iter().filter(|&&buildable| buildable).count();
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn buildable_mask(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        max_slope_deg: f32,
+    ) -> Result<(u32, u32, Vec<bool>)> {
+        let region = self.region(from, to)?;
+        let extended = Region {
+            row: region.row - 1,
+            col: region.col - 1,
+            width: region.width + 2,
+            height: region.height + 2,
+            ..region
+        };
+        let stride = extended.width as usize;
+        let mut samples = vec![0; extended.len()];
+        for (row, out) in samples.chunks_mut(stride).enumerate() {
+            self.read_region_row(&extended, row as u32, out)?;
+        }
+
+        let max_slope = (max_slope_deg as f64).to_radians().tan();
+        let (lat0, _) = region.north_west();
+        let spacing = 1.0 / (region.resolution.height() - 1) as f64;
+        let mut mask = Vec::with_capacity(region.len());
+        for row in 0..region.height as usize {
+            let lat = (lat0 as f64 - row as f64 * spacing) as f32;
+            let (dx, dy) = region.resolution.cell_size_m(lat);
+            for col in 0..region.width as usize {
+                let z = |i: usize, j: usize| samples[col + j + (row + i) * stride];
+                let window = [0, 1, 2].map(|i| [0, 1, 2].map(|j| z(i, j)));
+                if window.iter().flatten().any(|&h| self.is_void_height(h)) {
+                    mask.push(false);
+                    continue;
+                }
+                let z = window.map(|row| row.map(|h| h as f64));
+                let east = ((z[0][2] + 2.0 * z[1][2] + z[2][2])
+                    - (z[0][0] + 2.0 * z[1][0] + z[2][0]))
+                    / (8.0 * dx as f64);
+                let north = ((z[0][0] + 2.0 * z[0][1] + z[0][2])
+                    - (z[2][0] + 2.0 * z[2][1] + z[2][2]))
+                    / (8.0 * dy as f64);
+                mask.push(east.hypot(north) <= max_slope);
+            }
+        }
+        Ok((region.width, region.height, mask))
+    }
+
+    /// Returns the pits of a region: the samples strictly lower than their 8 neighbors, as
+    /// `(lat, lng, height)`, row-major from the north-west.
+    ///
+    /// See [`Tiles::fill_region`] for the region selection. The neighbors of the samples on the
+    /// region edges are read beyond the region, from the neighboring tiles if needed, so that
+    /// splitting a region does not add or lose pits. A sample which is a void, or next to a
+    /// void, is never a pit.
+    ///
+    /// Pits are the single-sample sinks, where the water of a flow line stops (see
+    /// [`Tiles::steepest_path`]): actual depressions, or radar artifacts to fill before a flow
+    /// analysis. The minimum is strict: a flat sink of several equal samples (a plateau, or a
+    /// lake) has no sample lower than all its neighbors, and is not returned.
+    ///
+    /// # Errors
+    ///
+    /// * The errors of [`Tiles::fill_region`], for the region extended by one sample on each
+    ///   side.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use easy_srtm::Tiles;
+    /// let tiles = Tiles::new("the_folder_path");
+    /// for (lat, lng, height) in tiles.find_pits((49.2, -1.7), (49.1, -1.6))? {
+    ///     println!("sink at ({}, {}): {} m", lat, lng, height);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn find_pits(&self, from: (f32, f32), to: (f32, f32)) -> Result<Vec<(f32, f32, i16)>> {
+        let region = self.region(from, to)?;
+        let extended = Region {
+            row: region.row - 1,
+            col: region.col - 1,
+            width: region.width + 2,
+            height: region.height + 2,
+            ..region
+        };
+        let stride = extended.width as usize;
+        // the rows above, at and below the current row of the region
+        let mut rows = vec![0; 3 * stride];
+        for (row, out) in rows.chunks_mut(stride).take(2).enumerate() {
+            self.read_region_row(&extended, row as u32, out)?;
+        }
+
+        let (lat0, lng0) = region.north_west();
+        let (n, m) = (
+            (region.resolution.width() - 1) as f64,
+            (region.resolution.height() - 1) as f64,
+        );
+        let mut pits = Vec::new();
+        for row in 0..region.height as usize {
+            let below = (row + 2) % 3;
+            self.read_region_row(
+                &extended,
+                row as u32 + 2,
+                &mut rows[below * stride..(below + 1) * stride],
+            )?;
+            let z = |i: usize, col: usize| rows[col + (row + i) % 3 * stride];
+            for col in 0..region.width as usize {
+                let center = z(1, col + 1);
+                let neighbors = [0, 1, 2]
+                    .into_iter()
+                    .flat_map(|i| [0, 1, 2].map(|j| (i, j)))
+                    .filter(|&ij| ij != (1, 1));
+                let mut heights = neighbors.map(|(i, j)| z(i, col + j));
+                if !self.is_void_height(center)
+                    && heights.all(|h| !self.is_void_height(h) && h > center)
+                {
+                    let lat = (lat0 as f64 - row as f64 / m) as f32;
+                    let lng = (lng0 as f64 + col as f64 / n) as f32;
+                    pits.push((lat, lng, center));
+                }
+            }
+        }
+        Ok(pits)
+    }
+
+    /// Reads the 3×3 samples around a single-sample region, row-major from the north-west.
+    fn neighborhood(&self, center: &Region) -> Result<[i16; 9]> {
+        let region = Region {
+            row: center.row - 1,
+            col: center.col - 1,
+            width: 3,
+            height: 3,
+            ..*center
+        };
+        let mut window = [0; 9];
+        for (row, out) in window.chunks_mut(3).enumerate() {
+            self.read_region_row(&region, row as u32, out)?;
+        }
+        Ok(window)
+    }
+}
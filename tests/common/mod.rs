@@ -0,0 +1,87 @@
+//! Synthetic tiles shared by the integration tests.
+//!
+//! Those tests do not need real SRTM files: tiles are generated in a temporary directory with
+//! heights computed from the geographic position of each sample, so that the overlapping edges
+//! of neighboring tiles hold the same values, as real tiles do.
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// SRTM3 tile side.
+pub const SRTM3_SIDE: u32 = 1201;
+/// SRTM1 tile side.
+pub const SRTM1_SIDE: u32 = 3601;
+
+/// A temporary directory removed when dropped.
+pub struct TileDir {
+    pub path: PathBuf,
+}
+
+impl TileDir {
+    /// Creates an empty directory, unique to the given tag.
+    pub fn new(tag: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("easy_srtm-{}-{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+}
+
+impl Drop for TileDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Returns the name of the tile whose south-west corner is (lat0, lng0).
+pub fn tile_name(lat0: i32, lng0: i32) -> String {
+    let ns = if lat0 >= 0 { "N" } else { "S" };
+    let ew = if lng0 >= 0 { "E" } else { "W" };
+    format!("{}{:02}{}{:03}.hgt", ns, lat0.abs(), ew, lng0.abs())
+}
+
+/// Writes the tile whose south-west corner is (lat0, lng0) in `dir`.
+///
+/// `height` receives the (lat, lng) position of each sample.
+pub fn write_tile<F>(dir: &Path, lat0: i32, lng0: i32, side: u32, height: F) -> PathBuf
+where
+    F: Fn(f64, f64) -> i16,
+{
+    write_tile_with(dir, lat0, lng0, (side, side), height)
+}
+
+/// Writes the tile of `(width, height)` samples whose south-west corner is (lat0, lng0) in
+/// `dir`.
+///
+/// `height` receives the (lat, lng) position of each sample.
+pub fn write_tile_with<F>(
+    dir: &Path,
+    lat0: i32,
+    lng0: i32,
+    (width, rows): (u32, u32),
+    height: F,
+) -> PathBuf
+where
+    F: Fn(f64, f64) -> i16,
+{
+    let (n, m) = ((width - 1) as f64, (rows - 1) as f64);
+    let mut bytes = Vec::with_capacity((width * rows * 2) as usize);
+    for y in 0..rows {
+        for x in 0..width {
+            let lat = lat0 as f64 + 1.0 - y as f64 / m;
+            let lng = lng0 as f64 + x as f64 / n;
+            bytes.extend_from_slice(&height(lat, lng).to_be_bytes());
+        }
+    }
+    let path = dir.join(tile_name(lat0, lng0));
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+/// Heights increasing with the distance to (0, 0), identical on overlapping tile edges.
+pub fn slope(lat: f64, lng: f64) -> i16 {
+    ((lat * 100.0).round() + (lng * 10.0).round()) as i16
+}
@@ -0,0 +1,302 @@
+mod common;
+
+use common::{slope, write_tile, write_tile_with, TileDir, SRTM1_SIDE, SRTM3_SIDE};
+use easy_srtm::{Resolution, SrtmError, Tiles};
+
+/// Validates that tiles are read from the first directory containing them.
+#[test]
+fn it_reads_tiles_from_several_directories() {
+    let (first, second) = (TileDir::new("directories-1"), TileDir::new("directories-2"));
+    write_tile(&first.path, 49, -2, SRTM3_SIDE, |_, _| 1);
+    write_tile(&second.path, 49, -2, SRTM1_SIDE, |_, _| 2);
+    write_tile(&second.path, 49, -1, SRTM3_SIDE, slope);
+
+    let tiles = Tiles::builder(&first.path).directory(&second.path).build();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), 1);
+    assert_eq!(tiles.elevation(49.5, -0.5).unwrap(), slope(49.5, -0.5));
+    assert!(tiles.elevation(48.5, -0.5).is_err());
+
+    let conflicts = tiles.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    let (name, paths) = &conflicts[0];
+    assert_eq!(name, "N49W002.hgt");
+    assert_eq!(paths[0], first.path.join(name));
+    assert_eq!(paths[1], second.path.join(name));
+}
+
+/// Validates the retrieval of every copy of a tile.
+#[test]
+fn it_reads_elevation_from_every_resolution() {
+    let (first, second) = (TileDir::new("resolutions-1"), TileDir::new("resolutions-2"));
+    write_tile(&first.path, 49, -2, SRTM1_SIDE, |_, _| 1);
+    write_tile(&second.path, 49, -2, SRTM3_SIDE, |_, _| 3);
+
+    let tiles = Tiles::builder(&first.path).directory(&second.path).build();
+    let heights = tiles.elevation_all_resolutions(49.5, -1.5).unwrap();
+    assert_eq!(
+        heights,
+        vec![(Resolution::SRTM1, 1), (Resolution::SRTM3, 3)]
+    );
+    assert!(tiles.elevation_all_resolutions(48.5, -1.5).is_err());
+}
+
+/// Validates the resolution detection of the tiles covering geopositions.
+#[test]
+fn it_detects_the_resolution_at_a_geoposition() {
+    let dir = TileDir::new("resolution_at");
+    write_tile(&dir.path, 49, -2, SRTM1_SIDE, slope);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(tiles.resolution_at(49.5, -1.5).unwrap(), Resolution::SRTM1);
+    assert_eq!(tiles.resolution_at(49.5, -0.5).unwrap(), Resolution::SRTM3);
+    assert!(tiles.resolution_at(48.5, -0.5).is_err());
+
+    // the opened tile is reused by the following lookups
+    std::fs::remove_file(dir.path.join("N49W002.hgt")).unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+}
+
+/// Validates the tiles directory read from an environment variable.
+#[test]
+fn it_reads_the_directory_from_the_environment() {
+    let dir = TileDir::new("from_env");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+
+    std::env::set_var("EASY_SRTM_TEST_DIR", &dir.path);
+    let tiles = Tiles::from_env_var("EASY_SRTM_TEST_DIR").unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+
+    let error = Tiles::from_env_var("EASY_SRTM_TEST_UNSET").err().unwrap();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::MissingEnvVar(_))
+    ));
+    std::env::set_var("EASY_SRTM_TEST_MISSING", dir.path.join("missing"));
+    assert!(Tiles::from_env_var("EASY_SRTM_TEST_MISSING").is_err());
+}
+
+/// Validates that opening a directory checks its existence.
+#[test]
+fn it_opens_existing_directories_only() {
+    let dir = TileDir::new("open");
+    let path = write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+
+    let tiles = Tiles::open(&dir.path).unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+
+    let error = Tiles::open(dir.path.join("missing")).err().unwrap();
+    let io = error.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+    assert!(format!("{:#}", error).contains("missing"));
+    let error = Tiles::open(path).err().unwrap();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::InvalidArgument(_))
+    ));
+}
+
+/// Validates the resolution reported with each elevation of a sequence.
+#[test]
+fn it_reports_the_resolution_of_each_elevation() {
+    let dir = TileDir::new("elevations_with_resolution");
+    write_tile(&dir.path, 49, -2, SRTM1_SIDE, |_, _| 1);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            3
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let points = [
+        (49.2, -1.5),
+        (49.2, -0.5),
+        (49.7, -0.5),
+        (0.0, -30.0),
+        (49.2, -1.5),
+    ];
+
+    let mut elevations = tiles.elevations_with_resolution(points);
+    assert_eq!(
+        elevations.next().unwrap().unwrap(),
+        (49.2, -1.5, Some(1), Resolution::SRTM1)
+    );
+    assert_eq!(
+        elevations.next().unwrap().unwrap(),
+        (49.2, -0.5, Some(3), Resolution::SRTM3)
+    );
+    assert_eq!(
+        elevations.next().unwrap().unwrap(),
+        (49.7, -0.5, None, Resolution::SRTM3)
+    );
+    let error = elevations.next().unwrap().unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::MissingTile(_))
+    ));
+    assert!(elevations.next().is_none());
+}
+
+/// Validates the lookup of the finest tile available.
+#[test]
+fn it_reads_elevation_from_the_finest_tile() {
+    let srtm3 = TileDir::new("best_srtm3");
+    let srtm1 = TileDir::new("best_srtm1");
+    write_tile(&srtm3.path, 49, -2, SRTM3_SIDE, |_, _| 3);
+    write_tile(&srtm1.path, 49, -2, SRTM1_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            1
+        }
+    });
+    write_tile(&srtm3.path, 49, -1, SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            3
+        }
+    });
+    let tiles = Tiles::builder(&srtm3.path).directory(&srtm1.path).build();
+
+    assert_eq!(tiles.elevation(49.2, -1.5).unwrap(), 3);
+    assert_eq!(
+        tiles.elevation_best(49.2, -1.5).unwrap(),
+        (Resolution::SRTM1, 1)
+    );
+    assert_eq!(
+        tiles.elevation_best(49.7, -1.5).unwrap(),
+        (Resolution::SRTM3, 3)
+    );
+    assert_eq!(
+        tiles.elevation_best(49.2, -0.5).unwrap(),
+        (Resolution::SRTM3, 3)
+    );
+    assert_eq!(
+        tiles.elevation_best(49.7, -0.5).unwrap(),
+        (Resolution::SRTM3, easy_srtm::VOID)
+    );
+    assert!(tiles.elevation_best(10.0, 10.0).is_err());
+
+    let tiles = Tiles::builder(&srtm1.path).directory(&srtm3.path).build();
+    assert_eq!(
+        tiles.elevation_best(49.2, -1.5).unwrap(),
+        (Resolution::SRTM1, 1)
+    );
+}
+
+/// Validates the overrides of the copy read by elevation, in the lookup of the finest tile.
+#[test]
+fn it_overrides_the_selected_copy_of_the_finest_tile() {
+    let srtm3 = TileDir::new("best_override_srtm3");
+    let srtm1 = TileDir::new("best_override_srtm1");
+    write_tile(&srtm3.path, 49, -2, SRTM3_SIDE, |_, _| 3);
+    write_tile(&srtm1.path, 49, -2, SRTM1_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            1
+        }
+    });
+    write_tile(&srtm3.path, 49, -1, SRTM3_SIDE, |_, _| easy_srtm::VOID);
+    let tiles = Tiles::builder(&srtm3.path).directory(&srtm1.path).build();
+
+    // a filled void of a SRTM3 tile without any other copy
+    tiles.override_sample(49.7, -0.5, 77).unwrap();
+    assert_eq!(
+        tiles.elevation_best(49.7, -0.5).unwrap(),
+        (Resolution::SRTM3, 77)
+    );
+    // the SRTM3 copy is read by elevation: its override fills the void of the SRTM1 copy
+    tiles.override_sample(49.7, -1.5, 33).unwrap();
+    assert_eq!(
+        tiles.elevation_best(49.7, -1.5).unwrap(),
+        (Resolution::SRTM3, 33)
+    );
+    // a valid sample of a finer copy still wins
+    tiles.override_sample(49.2, -1.5, 33).unwrap();
+    assert_eq!(
+        tiles.elevation_best(49.2, -1.5).unwrap(),
+        (Resolution::SRTM1, 1)
+    );
+    assert_eq!(
+        tiles.elevation_all_resolutions(49.7, -1.5).unwrap(),
+        vec![(Resolution::SRTM3, 3), (Resolution::SRTM1, easy_srtm::VOID)]
+    );
+}
+
+/// Validates the lookups in tiles of configured dimensions, with fewer columns than rows.
+#[test]
+fn it_reads_tiles_of_configured_dimensions() {
+    let dir = TileDir::new("dimensions");
+    // global column and row indices, 1/600° and 1/1200° apart
+    let index = |lat: f64, lng: f64| {
+        ((lng - 25.0) * 600.0).round() as i16 + ((lat - 65.0) * 1200.0).round() as i16 * 10
+    };
+    for lng0 in [25, 26] {
+        write_tile_with(&dir.path, 65, lng0, (601, SRTM3_SIDE), index);
+    }
+    let dimensions = Resolution::Dimensions {
+        width: 601,
+        height: SRTM3_SIDE,
+    };
+    let tiles = Tiles::builder(&dir.path)
+        .dimensions(601, SRTM3_SIDE)
+        .build();
+
+    for (lat, lng) in [(65.5, 25.5), (65.25, 25.1), (65.0, 25.0), (65.999, 25.999)] {
+        let expect = index(lat as f64, lng as f64);
+        assert_eq!(
+            tiles.elevation(lat, lng).unwrap(),
+            expect,
+            "({}, {})",
+            lat,
+            lng
+        );
+    }
+    let between = tiles
+        .elevation_interpolated(65.5, 25.5 + 1.0 / 1200.0)
+        .unwrap();
+    assert!((between - 6300.5).abs() < 0.01, "{}", between);
+    assert_eq!(
+        tiles.covering_tile(65.5, 25.5).unwrap().resolution,
+        dimensions
+    );
+
+    let grid = tiles.grid((65.6, 25.9), (65.5, 26.1)).unwrap();
+    assert_eq!(
+        (grid.width, grid.height, grid.resolution),
+        (121, 121, dimensions)
+    );
+    for (col, row) in [(0, 0), (60, 0), (61, 120), (120, 60)] {
+        let (lat, lng) = grid.position(col, row);
+        assert_eq!(grid.get(col, row), Some(index(lat as f64, lng as f64)));
+    }
+    let tile = tiles.load_tile(65.5, 25.5).unwrap();
+    assert_eq!((tile.width, tile.height), (601, SRTM3_SIDE));
+
+    let error = Tiles::new(&dir.path).elevation(65.5, 25.5).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::ResolutionError)
+    ));
+}
+
+/// Validates the lookups in tiles whose file names are not in the canonical case.
+#[test]
+fn it_reads_tiles_with_lowercase_names() {
+    let dir = TileDir::new("lowercase");
+    let tile = write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let lowercase = dir.path.join("n49w002.HGT");
+    std::fs::rename(tile, &lowercase).unwrap();
+
+    let tiles = Tiles::new(&dir.path);
+    assert!(tiles.has_tile("N49W002.hgt"));
+    assert_eq!(tiles.covering_tile(49.5, -1.5).unwrap().path, lowercase);
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+    assert_eq!(
+        tiles.elevation_all_resolutions(49.5, -1.5).unwrap(),
+        vec![(Resolution::SRTM3, slope(49.5, -1.5))]
+    );
+}
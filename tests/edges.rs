@@ -0,0 +1,269 @@
+mod common;
+
+use common::{slope, write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{haversine_m, Resolution, TieBreak, TileBoundary, Tiles};
+
+/// Validates that points exactly on tile edges read the overlapping sample.
+#[test]
+fn it_reads_the_overlapping_sample_on_tile_edges() {
+    let dir = TileDir::new("edges");
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    let tiles = Tiles::new(&dir.path);
+
+    let positions = vec![
+        (50.0, -1.0),
+        (50.0, -1.5),
+        (49.5, -1.0),
+        (50.5, -1.0),
+        (50.0, -0.5),
+    ];
+
+    positions.into_iter().for_each(|(lat, lng): (f32, f32)| {
+        let h = tiles.elevation(lat, lng).unwrap();
+        let expect = slope(lat as f64, lng as f64);
+        assert!(expect == h, "Failed for lat:{}, lng:{})", lat, lng);
+    });
+}
+
+/// Validates that the south-west convention reads edges from the southern and western tiles.
+#[test]
+fn it_selects_edge_tiles_with_the_configured_boundary() {
+    let dir = TileDir::new("boundary");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::builder(&dir.path)
+        .boundary(TileBoundary::SouthWest)
+        .build();
+
+    assert_eq!(tiles.tile_name(50.0, -1.0), "N49W002.hgt");
+    assert_eq!(tiles.tile_name(0.0, 0.0), "S01W001.hgt");
+    assert_eq!(tiles.elevation(50.0, -1.0).unwrap(), slope(50.0, -1.0));
+    assert_eq!(tiles.elevation(49.5, -1.0).unwrap(), slope(49.5, -1.0));
+    assert!(Tiles::new(&dir.path).elevation(50.0, -1.0).is_err());
+}
+
+/// Validates the distances to the tile edges.
+#[test]
+fn it_computes_the_distance_to_tile_edges() {
+    let dir = TileDir::new("edge_distance");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+
+    let check = |lat, lng, expect| {
+        let result = tiles.edge_distance_cells(lat, lng).unwrap();
+        assert!(result == expect, "failed for (l={:?}, g={:?})", lat, lng)
+    };
+
+    check(49.25, -1.5, (900, 300, 600, 600));
+    check(49.0, -2.0, (1200, 0, 1200, 0));
+    check(49.9999, -1.0001, (0, 1200, 0, 1200));
+    assert!(tiles.edge_distance_cells(10.0, 10.0).is_err());
+}
+
+/// Validates that neighboring tiles hold the same samples on their shared edges.
+#[test]
+fn it_reads_identical_samples_on_shared_edges() {
+    let dir = TileDir::new("shared-edges");
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    let tiles = Tiles::new(&dir.path);
+    let (south_west, south_east) = (
+        tiles.pin_tile(49.5, -1.5).unwrap(),
+        tiles.pin_tile(49.5, -0.5).unwrap(),
+    );
+    let north_west = tiles.pin_tile(50.5, -1.5).unwrap();
+
+    for i in 0..SRTM3_SIDE {
+        let v = i as f32 / (SRTM3_SIDE - 1) as f32;
+        let (lat, lng) = (49.0 + v, -2.0 + v);
+        let east = south_west.elevation(lat, -1.0).unwrap();
+        assert_eq!(
+            east,
+            south_east.elevation(lat, -1.0).unwrap(),
+            "lat {}",
+            lat
+        );
+        assert_eq!(east, tiles.elevation_canonical(lat, -1.0).unwrap());
+        let north = south_west.elevation(50.0, lng).unwrap();
+        assert_eq!(
+            north,
+            north_west.elevation(50.0, lng).unwrap(),
+            "lng {}",
+            lng
+        );
+        assert_eq!(north, tiles.elevation_canonical(50.0, lng).unwrap());
+    }
+}
+
+/// Validates that the edge samples are read from a single tile, even when neighbors differ.
+#[test]
+fn it_reads_edge_samples_from_a_canonical_tile() {
+    let dir = TileDir::new("canonical");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 49);
+    write_tile(&dir.path, 50, -2, SRTM3_SIDE, |_, _| 50);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(tiles.elevation(49.99999, -1.5).unwrap(), 49);
+    assert_eq!(tiles.elevation(50.0, -1.5).unwrap(), 50);
+    for lat in [49.99999, 50.0, 50.00001] {
+        assert_eq!(tiles.elevation_canonical(lat, -1.5).unwrap(), 50);
+    }
+    assert_eq!(tiles.elevation_canonical(49.999, -1.5).unwrap(), 49);
+
+    let tiles = Tiles::builder(&dir.path)
+        .boundary(TileBoundary::SouthWest)
+        .build();
+    for lat in [49.99999, 50.0, 50.00001] {
+        assert_eq!(tiles.elevation_canonical(lat, -1.5).unwrap(), 49);
+    }
+}
+
+/// Validates the comparison of the round and floor sample selections.
+#[test]
+fn it_compares_the_rounding_conventions() {
+    let dir = TileDir::new("debug");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        (((50.0 - lat) * 1200.0).round() * 10000.0 + ((lng + 2.0) * 1200.0).round()) as i16
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    let debug = tiles.elevation_debug(49.9996, -1.9996).unwrap();
+    assert!(!debug.agrees());
+    assert_eq!(debug.round.tile, "N49W002.hgt");
+    assert_eq!(debug.round.pixel, (0, 0));
+    assert_eq!(debug.floor.pixel, (0, 1));
+    assert_eq!(
+        debug.round.height,
+        tiles.elevation(49.9996, -1.9996).unwrap()
+    );
+    assert_eq!(debug.floor.height, 10000);
+
+    let debug = tiles.elevation_debug(49.5001, -1.4999).unwrap();
+    assert!(debug.agrees());
+    assert_eq!(debug.floor.pixel, (600, 600));
+}
+
+/// Validates the sample selections on the antimeridian, the eastern edge of E179 with the
+/// south-west convention.
+#[test]
+fn it_compares_the_rounding_conventions_on_the_antimeridian() {
+    let dir = TileDir::new("debug_antimeridian");
+    write_tile(&dir.path, 45, 179, SRTM3_SIDE, |_, lng| {
+        ((lng - 179.0) * 1200.0).round() as i16
+    });
+    let tiles = Tiles::builder(&dir.path)
+        .boundary(TileBoundary::SouthWest)
+        .build();
+
+    for lng in [180.0, -180.0] {
+        let debug = tiles.elevation_debug(45.5, lng).unwrap();
+        assert!(debug.agrees());
+        assert_eq!(debug.round.tile, "N45E179.hgt");
+        assert_eq!(debug.pixel, (1200.0, 600.0));
+        assert_eq!(debug.floor.pixel, (1200, 600));
+        assert_eq!(debug.floor.height, tiles.elevation(45.5, lng).unwrap());
+        assert_eq!(debug.floor.height, 1200);
+        assert_eq!(tiles.elevation_interpolated(45.5, lng).unwrap(), 1200.0);
+    }
+}
+
+/// Validates the distance between the geopositions and the samples read for them.
+#[test]
+fn it_computes_the_snapping_error() {
+    let dir = TileDir::new("snap_error");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let cell = 1.0 / 1200.0;
+
+    assert!(tiles.snap_error_m(49.5, -1.5).unwrap() < 0.01);
+    let error = tiles.snap_error_m(49.5 + cell / 4.0, -1.5).unwrap();
+    assert!((error - haversine_m((49.5, -1.5), (49.5 + cell / 4.0, -1.5))).abs() < 0.01);
+    assert!((error - 23.2).abs() < 0.5, "{}", error);
+    let error = tiles
+        .snap_error_m(49.5 - 0.49 * cell, -1.5 + 0.49 * cell)
+        .unwrap();
+    let diagonal = haversine_m((49.5, -1.5), (49.5 - cell, -1.5 + cell));
+    assert!(error < diagonal / 2.0 && error > diagonal * 0.48);
+}
+
+/// Validates the tie breaks of the geopositions exactly halfway between two samples.
+#[test]
+fn it_breaks_ties_between_two_samples() {
+    let dir = TileDir::new("elevation_nearest");
+    // the column, and the row modulo 16
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        let (col, row) = (
+            ((lng + 2.0) * 1200.0).round(),
+            ((50.0 - lat) * 1200.0).round(),
+        );
+        (col + 2000.0 * (row % 16.0)) as i16
+    });
+    let tiles = Tiles::new(&dir.path);
+    // 3/32° east and 1/32° north of the corner: the pixels 112.5 east and 37.5 north
+    let (lat, lng) = (49.03125, -1.90625);
+
+    assert_eq!(
+        tiles.elevation_nearest(lat, lng, TieBreak::Floor).unwrap(),
+        112 + 2000 * (1163 % 16)
+    );
+    assert_eq!(
+        tiles.elevation_nearest(lat, lng, TieBreak::Ceil).unwrap(),
+        113 + 2000 * (1162 % 16)
+    );
+    assert_eq!(
+        tiles.elevation_nearest(lat, lng, TieBreak::Even).unwrap(),
+        112 + 2000 * (1162 % 16)
+    );
+    assert_eq!(
+        tiles.elevation(lat, lng).unwrap(),
+        tiles
+            .elevation_nearest(lat, lng, TieBreak::default())
+            .unwrap()
+    );
+
+    let inside = tiles.elevation(49.5001, -1.4999).unwrap();
+    for tie in [TieBreak::Floor, TieBreak::Ceil, TieBreak::Even] {
+        assert_eq!(
+            tiles.elevation_nearest(49.5001, -1.4999, tie).unwrap(),
+            inside
+        );
+    }
+
+    // the overrides apply to the selected sample
+    tiles.override_sample(lat, lng, 7).unwrap();
+    assert_eq!(
+        tiles.elevation_nearest(lat, lng, TieBreak::Ceil).unwrap(),
+        7
+    );
+    assert_eq!(
+        tiles.elevation_nearest(lat, lng, TieBreak::Floor).unwrap(),
+        112 + 2000 * (1163 % 16)
+    );
+}
+
+/// Validates the pixels of positions inside and on the edges of a tile.
+#[test]
+fn it_maps_positions_to_tile_pixels() {
+    let dir = TileDir::new("pixel_of");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(
+        tiles.pixel_of(49.5, -1.5).unwrap(),
+        (Resolution::SRTM3, 600, 600)
+    );
+    assert_eq!(
+        tiles.pixel_of(49.0, -2.0).unwrap(),
+        (Resolution::SRTM3, 0, 1200)
+    );
+    assert_eq!(
+        tiles.pixel_of(49.9999, -1.0001).unwrap(),
+        (Resolution::SRTM3, 1200, 0)
+    );
+    let (_, x, y) = tiles.pixel_of(49.1, -1.6).unwrap();
+    let grid = tiles.load_tile(49.1, -1.6).unwrap();
+    assert_eq!(grid.get(x, y), Some(tiles.elevation(49.1, -1.6).unwrap()));
+    assert!(tiles.pixel_of(10.5, 10.5).is_err());
+}
@@ -1,5 +1,6 @@
-use dotenv;
-use easy_srtm::Tiles;
+mod common;
+
+use easy_srtm::{Resolution, SrtmError, Tiles};
 
 /// Validates the elevetion retrieval.
 ///
@@ -42,3 +43,397 @@ fn it_retrieves_heights_iterator() {
     // let geo_heights = tiles.elevations(from, to);
     // let (latitude, longitude, height) = geo_heights.next();
 }
+
+/// Validates the default height of missing tiles and voids.
+#[test]
+fn it_defaults_elevation_of_missing_tiles_and_voids() {
+    let dir = common::TileDir::new("elevation_or");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            42
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(tiles.elevation_or(49.25, -1.5, 0), 42);
+    assert_eq!(tiles.elevation_or(49.75, -1.5, 0), 0);
+    assert_eq!(tiles.elevation_or(0.0, -30.0, -1), -1);
+}
+
+/// Validates that errors other than missing tiles are not defaulted.
+#[test]
+#[should_panic]
+fn it_panics_on_corrupted_tiles_with_a_default() {
+    let dir = common::TileDir::new("elevation_or_corrupted");
+    std::fs::write(dir.path.join("N49W002.hgt"), [0; 10]).unwrap();
+    let tiles = Tiles::new(&dir.path);
+    tiles.elevation_or(49.25, -1.5, 0);
+}
+
+/// Validates the detection of voids, with the default and a configured void value.
+#[test]
+fn it_detects_voids() {
+    let dir = common::TileDir::new("is_void");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            -9999
+        }
+    });
+
+    let tiles = Tiles::new(&dir.path);
+    assert!(!tiles.is_void(49.25, -1.5).unwrap());
+    assert!(tiles.is_void(49.75, -1.5).unwrap());
+    assert_eq!(tiles.elevation_opt(49.25, -1.5).unwrap(), Some(-9999));
+    assert_eq!(tiles.elevation_opt(49.75, -1.5).unwrap(), None);
+
+    let tiles = Tiles::builder(&dir.path).void_value(-9999).build();
+    assert!(tiles.is_void(49.25, -1.5).unwrap());
+    assert!(!tiles.is_void(49.75, -1.5).unwrap());
+    assert!(tiles.is_void(10.0, 10.0).is_err());
+}
+
+/// Validates that heights below the threshold are treated as voids.
+#[test]
+fn it_treats_heights_below_a_threshold_as_voids() {
+    let dir = common::TileDir::new("void_below");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            -500
+        } else {
+            -100
+        }
+    });
+
+    let tiles = Tiles::builder(&dir.path).void_below(-400).build();
+    assert_eq!(tiles.elevation_opt(49.25, -1.5).unwrap(), Some(-100));
+    assert_eq!(tiles.elevation_opt(49.75, -1.5).unwrap(), None);
+    assert_eq!(tiles.elevation(49.75, -1.5).unwrap(), -500);
+    assert_eq!(
+        tiles.elevation_robust(49.5 + 1.0 / 2400.0, -1.5).unwrap(),
+        -100.0
+    );
+
+    let tiles = Tiles::new(&dir.path);
+    assert_eq!(tiles.elevation_opt(49.75, -1.5).unwrap(), Some(-500));
+}
+
+/// Validates the sample retrieval from a raw grid with an explicit side.
+#[test]
+fn it_reads_samples_with_an_explicit_side() {
+    let dir = common::TileDir::new("read_sample_with");
+    let side = 5u32;
+    let bytes: Vec<u8> = (0..side * side)
+        .flat_map(|i| (i as i16).to_be_bytes())
+        .collect();
+    let path = dir.path.join("export.raw");
+    std::fs::write(&path, bytes).unwrap();
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(tiles.read_sample_with(&path, side, 0, 0).unwrap(), 0);
+    assert_eq!(tiles.read_sample_with(&path, side, 3, 2).unwrap(), 13);
+    assert_eq!(tiles.read_sample_with(&path, 4, 3, 2).unwrap(), 11);
+    assert!(tiles.read_sample_with(&path, side, 5, 0).is_err());
+    assert!(tiles.read_sample_with(&path, 6, 0, 0).is_err());
+}
+
+/// Validates the resolution detection from a tile path.
+#[test]
+fn it_detects_the_resolution_of_a_tile_path() {
+    let dir = common::TileDir::new("resolution_path");
+    let path = common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, common::slope);
+    let other = dir.path.join("other.hgt");
+    std::fs::write(&other, [0; 10]).unwrap();
+
+    assert_eq!(
+        Resolution::try_from(path.as_path()).unwrap(),
+        Resolution::SRTM3
+    );
+    assert!(matches!(
+        Resolution::try_from(other.as_path()),
+        Err(SrtmError::ResolutionError)
+    ));
+    assert!(matches!(
+        Resolution::try_from(dir.path.join("missing.hgt").as_path()),
+        Err(SrtmError::Io(_))
+    ));
+}
+
+/// Validates the height range of a tile.
+#[test]
+fn it_computes_the_extent_of_a_tile() {
+    let dir = common::TileDir::new("tile_extent");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, lng| {
+        if lat > 49.9 {
+            easy_srtm::VOID
+        } else {
+            ((lat - 49.0) * 1000.0 + (lng + 2.0) * 100.0).round() as i16
+        }
+    });
+    common::write_tile(&dir.path, 49, -1, common::SRTM3_SIDE, |_, _| {
+        easy_srtm::VOID
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    let (min, max) = tiles.tile_extent(49.5, -1.5).unwrap();
+    assert_eq!((min, max), (0, 1000));
+    assert!(matches!(
+        tiles.tile_extent(49.5, -0.5).unwrap_err().downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+}
+
+/// Validates the elevation relative to a reference geoposition.
+#[test]
+fn it_computes_elevations_relative_to_a_reference() {
+    let dir = common::TileDir::new("elevation_relative");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, lng| {
+        if lat > 49.9 {
+            easy_srtm::VOID
+        } else {
+            common::slope(lat, lng)
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let home = (49.2, -1.8);
+    let expect = |lat, lng| {
+        (tiles.elevation(lat, lng).unwrap() - tiles.elevation(home.0, home.1).unwrap()) as f32
+    };
+
+    assert_eq!(tiles.elevation_relative(home.0, home.1, home).unwrap(), 0.0);
+    assert_eq!(
+        tiles.elevation_relative(49.7, -1.3, home).unwrap(),
+        expect(49.7, -1.3)
+    );
+
+    assert!(matches!(
+        tiles
+            .elevation_relative(49.95, -1.3, home)
+            .unwrap_err()
+            .downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert!(tiles.elevation_relative(49.2, -1.8, (49.95, -1.3)).is_err());
+    assert!(tiles.elevation_relative(10.0, 10.0, home).is_err());
+}
+
+/// Validates that the elevations iterator skips voids and stops at the first fatal error.
+#[test]
+fn it_fuses_the_elevations_after_a_fatal_error() {
+    let dir = common::TileDir::new("elevations");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            42
+        }
+    });
+    std::fs::write(dir.path.join("N49W001.hgt"), [0; 10]).unwrap();
+    let points = [(49.25, -1.5), (49.75, -1.5), (0.0, -30.0), (49.25, -1.5)];
+
+    let tiles = Tiles::builder(&dir.path).allow_missing(true).build();
+    let heights = tiles.elevations(points).collect::<anyhow::Result<Vec<_>>>();
+    assert_eq!(heights.unwrap(), vec![Some(42), None, None, Some(42)]);
+
+    let mut elevations = tiles.elevations([(49.25, -1.5), (49.25, -0.5), (49.25, -1.5)]);
+    assert_eq!(elevations.next().unwrap().unwrap(), Some(42));
+    assert!(elevations.next().unwrap().is_err());
+    assert!(elevations.next().is_none());
+
+    let tiles = Tiles::new(&dir.path);
+    let mut elevations = tiles.elevations(points);
+    assert_eq!(elevations.next().unwrap().unwrap(), Some(42));
+    assert_eq!(elevations.next().unwrap().unwrap(), None);
+    let error = elevations.next().unwrap().unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::MissingTile(_))
+    ));
+    assert!(elevations.next().is_none());
+}
+
+/// Validates that the batch keeps a result for each geoposition, in order.
+#[test]
+fn it_returns_the_elevations_of_a_batch_in_order() {
+    let dir = common::TileDir::new("elevations_opt_batch");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, _| {
+        if lat > 49.5 {
+            easy_srtm::VOID
+        } else {
+            42
+        }
+    });
+    let points = [
+        (0.0, -30.0),
+        (49.25, -1.5),
+        (0.5, -29.5),
+        (49.75, -1.5),
+        (49.3, -1.6),
+    ];
+
+    let tiles = Tiles::builder(&dir.path).allow_missing(true).build();
+    let heights = tiles.elevations_opt_batch(&points);
+    assert_eq!(heights.len(), 5);
+    for i in [0, 2] {
+        let error = heights[i].as_ref().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SrtmError::MissingTile(name)) if name == "N00W030.hgt"
+        ));
+    }
+    assert_eq!(heights[1].as_ref().unwrap(), &Some(42));
+    assert_eq!(heights[3].as_ref().unwrap(), &None);
+    assert_eq!(heights[4].as_ref().unwrap(), &Some(42));
+    // the tile of the 2 missing geopositions is searched once
+    assert_eq!(tiles.cache_stats().misses, 2);
+    assert!(tiles.elevations_opt_batch(&[]).is_empty());
+}
+
+/// Validates the timeout of the elevation when opening the tile blocks.
+#[cfg(unix)]
+#[test]
+fn it_times_out_when_opening_a_tile_blocks() {
+    use std::time::Duration;
+    let dir = common::TileDir::new("elevation_with_timeout");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, common::slope);
+    // opening a named pipe blocks until a writer opens it
+    let pipe = dir.path.join("N49W001.hgt");
+    let status = std::process::Command::new("mkfifo")
+        .arg(&pipe)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let tiles = Tiles::new(&dir.path);
+    let timeout = Duration::from_millis(100);
+
+    assert_eq!(
+        tiles.elevation_with_timeout(49.5, -1.5, timeout).unwrap(),
+        common::slope(49.5, -1.5)
+    );
+    assert_eq!(tiles.cache_stats().tiles.len(), 1);
+    assert_eq!(
+        (tiles.cache_stats().hits, tiles.cache_stats().misses),
+        (0, 1)
+    );
+    tiles.elevation_with_timeout(49.6, -1.6, timeout).unwrap();
+    assert_eq!(
+        (tiles.cache_stats().hits, tiles.cache_stats().misses),
+        (1, 1)
+    );
+    let error = tiles
+        .elevation_with_timeout(49.5, -0.5, timeout)
+        .unwrap_err();
+    assert!(matches!(error.downcast_ref(), Some(SrtmError::Timeout(_))));
+    let error = tiles
+        .elevation_with_timeout(0.0, -30.0, timeout)
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::MissingTile(_))
+    ));
+    assert_eq!(
+        (tiles.cache_stats().hits, tiles.cache_stats().misses),
+        (1, 3)
+    );
+
+    // unblocks the watchdog thread
+    std::fs::OpenOptions::new().write(true).open(&pipe).unwrap();
+}
+
+/// Validates the audit records of the successful lookups.
+#[test]
+fn it_records_the_samples_of_the_lookups() {
+    use std::sync::{Arc, Mutex};
+    let dir = common::TileDir::new("audit");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, common::slope);
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&records);
+    let tiles = Tiles::builder(&dir.path)
+        .audit(move |record| sink.lock().unwrap().push(record.clone()))
+        .build();
+
+    let height = tiles.elevation(49.25, -1.5).unwrap();
+    assert!(tiles.elevation(10.0, 10.0).is_err());
+    tiles.clone().elevation_opt(49.5, -1.75).unwrap();
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 2);
+    let record = &records[0];
+    assert_eq!((record.lat, record.lng), (49.25, -1.5));
+    assert_eq!(record.resolution, Resolution::SRTM3);
+    assert_eq!(record.sample.tile, "N49W002.hgt");
+    assert_eq!(record.sample.pixel, (600, 900));
+    assert_eq!(record.sample.height, height);
+    assert_eq!(records[1].sample.pixel, (300, 600));
+}
+
+/// Validates that overridden samples are returned by the lookups until cleared.
+#[test]
+fn it_overrides_samples_in_memory() {
+    let dir = common::TileDir::new("override_sample");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, |lat, lng| {
+        if lat > 49.9 {
+            easy_srtm::VOID
+        } else {
+            common::slope(lat, lng)
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let original = tiles.elevation(49.5, -1.5).unwrap();
+
+    tiles.override_sample(49.5, -1.5, 1234).unwrap();
+    tiles.override_sample(49.95, -1.5, 56).unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), 1234);
+    assert_eq!(tiles.elevation(49.5 + 0.0003, -1.5).unwrap(), 1234);
+    assert_eq!(tiles.elevation_opt(49.95, -1.5).unwrap(), Some(56));
+    assert_eq!(
+        tiles.elevation(49.25, -1.5).unwrap(),
+        common::slope(49.25, -1.5)
+    );
+
+    tiles.refresh().unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), 1234);
+    assert_eq!(tiles.clone().elevation(49.5, -1.5).unwrap(), 1234);
+    assert_eq!(
+        Tiles::new(&dir.path).elevation(49.5, -1.5).unwrap(),
+        original
+    );
+
+    tiles.clear_overrides();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), original);
+    assert_eq!(tiles.elevation_opt(49.95, -1.5).unwrap(), None);
+    assert!(tiles.override_sample(10.0, 10.0, 0).is_err());
+}
+
+/// Validates the elevation from latitude and longitude in radians.
+#[test]
+fn it_gets_the_elevation_from_radians() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let dir = common::TileDir::new("elevation_rad");
+    common::write_tile(&dir.path, 49, -2, common::SRTM3_SIDE, common::slope);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(
+        tiles
+            .elevation_rad(49.5f64.to_radians(), (-1.5f64).to_radians())
+            .unwrap(),
+        tiles.elevation(49.5, -1.5).unwrap()
+    );
+    for (lat, lng) in [(FRAC_PI_2 + 0.01, 0.0), (0.5, -PI - 0.01), (f64::NAN, 0.0)] {
+        let error = tiles.elevation_rad(lat, lng).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SrtmError::OutOfRange { .. })
+        ));
+    }
+    assert!(matches!(
+        tiles
+            .elevation_rad(FRAC_PI_2, PI)
+            .unwrap_err()
+            .downcast_ref(),
+        Some(SrtmError::MissingTile(_))
+    ));
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::{slope, write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{elevation_from_file, Resolution, SrtmError, TileInfo, Tiles};
+
+/// Validates the elevation retrieval from a pinned tile.
+#[test]
+fn it_reads_elevation_from_a_pinned_tile() {
+    let dir = TileDir::new("handle");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    write_tile(&dir.path, 50, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let tile = tiles.pin_tile(49.1, -1.6).unwrap();
+
+    assert_eq!(tile.name(), "N49W002.hgt");
+    assert_eq!(tile.resolution(), Resolution::SRTM3);
+
+    let positions = vec![(49.5, -1.5), (49.123, -1.987), (50.0, -1.5), (49.0, -2.0)];
+    positions.into_iter().for_each(|(lat, lng): (f32, f32)| {
+        let h = tile.elevation(lat, lng).unwrap();
+        assert!(tiles.elevation(lat, lng).unwrap() == h);
+    });
+    assert_eq!(tile.elevation(50.0, -1.0).unwrap(), slope(50.0, -1.0));
+    assert!(tile.elevation(50.1, -1.5).is_err());
+    assert!(tiles.pin_tile(10.0, 10.0).is_err());
+}
+
+/// Validates the raw access to the bytes of a tile.
+#[test]
+fn it_decodes_samples_from_tile_bytes() {
+    let dir = TileDir::new("tile-bytes");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let tile = tiles.tile_bytes(49.1, -1.6).unwrap();
+    let pinned = tiles.pin_tile(49.1, -1.6).unwrap();
+
+    assert_eq!(tile.resolution(), Resolution::SRTM3);
+    assert_eq!(tile.len() as u64, Resolution::SRTM3.file_size());
+    assert_eq!(tile.sample(0, 0), pinned.elevation(50.0, -2.0).unwrap());
+    assert_eq!(
+        tile.sample(1200, 1200),
+        pinned.elevation(49.0, -1.0).unwrap()
+    );
+    assert_eq!(
+        tile.sample(600, 300),
+        pinned.elevation(49.75, -1.5).unwrap()
+    );
+    assert_eq!(&tile[..2], &tile.sample(0, 0).to_be_bytes());
+}
+
+/// Validates the sample retrieval from a tile name and pixel coordinates.
+#[test]
+fn it_reads_samples_by_pixel() {
+    let dir = TileDir::new("sample_by_pixel");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(
+        tiles.sample_by_pixel("N49W002.hgt", 0, 0).unwrap(),
+        slope(50.0, -2.0)
+    );
+    assert_eq!(
+        tiles.sample_by_pixel("N49W002.hgt", 600, 300).unwrap(),
+        tiles.elevation(49.75, -1.5).unwrap()
+    );
+    assert_eq!(
+        tiles.sample_by_pixel("N49W002.hgt", 1200, 1200).unwrap(),
+        slope(49.0, -1.0)
+    );
+    let error = tiles.sample_by_pixel("N49W002.hgt", 1201, 0).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::InvalidArgument(_))
+    ));
+    assert!(tiles.sample_by_pixel("N49W001.hgt", 0, 0).is_err());
+}
+
+/// Validates the elevation retrieval from a tile path.
+#[test]
+fn it_reads_elevation_from_a_tile_path() {
+    let dir = TileDir::new("elevation_from_file");
+    let path = write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let renamed = dir.path.join("n49w002.HGT");
+    std::fs::copy(&path, &renamed).unwrap();
+    let other = dir.path.join("export.hgt");
+    std::fs::copy(&path, &other).unwrap();
+
+    assert_eq!(
+        elevation_from_file(&path, 49.5, -1.5).unwrap(),
+        slope(49.5, -1.5)
+    );
+    assert_eq!(
+        elevation_from_file(&renamed, 50.0, -1.0).unwrap(),
+        slope(50.0, -1.0)
+    );
+    let error = elevation_from_file(&path, 50.5, -1.5).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::OutsideTile { .. })
+    ));
+    let error = elevation_from_file(&other, 49.5, -1.5).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::InvalidArgument(_))
+    ));
+    assert!(elevation_from_file(dir.path.join("N10E010.hgt"), 10.5, 10.5).is_err());
+}
+
+/// Validates the lookups on the eastern edge of E179, the antimeridian.
+#[test]
+fn it_reads_the_antimeridian_edge_from_a_tile_path() {
+    let dir = TileDir::new("handle_antimeridian");
+    let path = write_tile(&dir.path, 45, 179, SRTM3_SIDE, |_, lng| {
+        ((lng - 179.0) * 1200.0).round() as i16
+    });
+
+    for lng in [180.0, -180.0, 179.5] {
+        let expect = if lng == 179.5 { 600 } else { 1200 };
+        assert_eq!(elevation_from_file(&path, 45.5, lng).unwrap(), expect);
+    }
+    for lng in [178.9, -179.9] {
+        let error = elevation_from_file(&path, 45.5, lng).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SrtmError::OutsideTile { .. })
+        ));
+    }
+}
+
+/// Validates the description of the tile covering a geoposition.
+#[test]
+fn it_describes_the_covering_tile() {
+    let dir = TileDir::new("covering_tile");
+    let path = write_tile(&dir.path, -17, -180, SRTM3_SIDE, slope);
+    std::fs::write(dir.path.join("N49W002.hgt"), [0; 10]).unwrap();
+    let tiles = Tiles::new(&dir.path);
+
+    let tile = tiles.covering_tile(-16.5, 180.0).unwrap();
+    assert_eq!(
+        tile,
+        TileInfo {
+            name: "S17W180.hgt".into(),
+            sw: (-17.0, -180.0),
+            ne: (-16.0, -179.0),
+            resolution: Resolution::SRTM3,
+            path,
+        }
+    );
+    assert!(tiles.cache_stats().tiles.is_empty());
+    assert!(matches!(
+        tiles.covering_tile(49.5, -1.5).unwrap_err().downcast_ref(),
+        Some(SrtmError::ResolutionError)
+    ));
+    assert!(matches!(
+        tiles.covering_tile(10.0, 10.0).unwrap_err().downcast_ref(),
+        Some(SrtmError::MissingTile(_))
+    ));
+}
@@ -0,0 +1,246 @@
+mod common;
+
+use common::{write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{Interp, PixelConvention, SrtmError, Tiles, VOID};
+
+/// Validates that the interpolation is exact on a linear terrain, inside and on tile edges.
+#[test]
+fn it_interpolates_elevation_between_samples() {
+    let dir = TileDir::new("interpolation");
+    let plane = |lat: f64, lng: f64| ((lat - 49.0) * 1000.0 + (lng + 2.0) * 500.0).round() as i16;
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, plane);
+    let tiles = Tiles::new(&dir.path);
+
+    let positions = vec![
+        (49.5, -1.5),
+        (49.123, -1.987),
+        (49.9999, -1.0001),
+        (49.0, -2.0),
+    ];
+
+    positions.into_iter().for_each(|(lat, lng): (f32, f32)| {
+        let h = tiles.elevation_interpolated(lat, lng).unwrap();
+        let expect = (lat - 49.0) * 1000.0 + (lng + 2.0) * 500.0;
+        assert!(
+            (expect - h).abs() < 1.0,
+            "Failed for lat:{}, lng:{})",
+            lat,
+            lng
+        );
+    });
+}
+
+/// Validates the fallbacks of the robust elevation around voids.
+#[test]
+fn it_falls_back_on_valid_samples_around_voids() {
+    let dir = TileDir::new("robust");
+    // a void at the (600, 600) sample, voids around the (900, 900) sample but in the column
+    // 903, and voids only around the (300, 300) sample
+    let terrain = |lat: f64, lng: f64| {
+        let (x, y) = (
+            ((lng + 2.0) * 1200.0).round(),
+            ((50.0 - lat) * 1200.0).round(),
+        );
+        let near = |c: f64| (x - c).abs() <= 3.0 && (y - c).abs() <= 3.0;
+        if (x, y) == (600.0, 600.0) || (near(900.0) && x != 903.0) || near(300.0) {
+            VOID
+        } else {
+            (x * 10.0 + y) as i16
+        }
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, terrain);
+    let tiles = Tiles::new(&dir.path);
+    let cell = 1.0 / 1200.0;
+
+    let h = tiles.elevation_robust(49.2, -1.8).unwrap();
+    assert!((h - tiles.elevation_interpolated(49.2, -1.8).unwrap() as f64).abs() < 1e-2);
+
+    // the south-west corner at (600, 600) is a void: the three other corners are averaged
+    let (lat, lng) = (49.5 + cell / 2.0, -1.5 + cell / 2.0);
+    let h = tiles.elevation_robust(lat as f32, lng as f32).unwrap();
+    let expect = (6610.0 + 6599.0 + 6609.0) / 3.0;
+    assert!((h - expect).abs() < 1e-2, "{} != {}", h, expect);
+
+    assert_eq!(tiles.elevation_robust(49.25, -1.25).unwrap(), 9930.0);
+    assert!(matches!(
+        tiles
+            .elevation_robust(49.75, -1.75)
+            .unwrap_err()
+            .downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert!(tiles.elevation_robust(10.0, 10.0).is_err());
+}
+
+/// Validates that the interpolation is continuous across tile seams.
+#[test]
+fn it_interpolates_continuously_across_tile_seams() {
+    let dir = TileDir::new("seams");
+    let terrain = |lat: f64, lng: f64| {
+        (1000.0 + 500.0 * (lat * 37.0).sin() + 300.0 * (lng * 23.0).cos()).round() as i16
+    };
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, terrain);
+    }
+    let tiles = Tiles::new(&dir.path);
+    // the terrain changes by up to 15 m from a sample to the next one: 0.2 m per 1e-5°
+    let (eps, tolerance) = (1e-5, 0.5);
+    let check = |(lat1, lng1): (f32, f32), (lat2, lng2): (f32, f32)| {
+        let h1 = tiles.elevation_interpolated(lat1, lng1).unwrap();
+        let h2 = tiles.elevation_interpolated(lat2, lng2).unwrap();
+        assert!(
+            (h1 - h2).abs() < tolerance,
+            "{} at ({}, {}) but {} at ({}, {})",
+            h1,
+            lat1,
+            lng1,
+            h2,
+            lat2,
+            lng2
+        );
+    };
+
+    for i in 0..50 {
+        let v = i as f32 / 50.0 + 0.0123;
+        // western and eastern sides of the -1° meridian
+        check((49.0 + v, -1.0 - eps), (49.0 + v, -1.0 + eps));
+        check((49.0 + v, -1.0 - eps), (49.0 + v, -1.0));
+        // southern and northern sides of the 50° parallel
+        check((50.0 - eps, -2.0 + v), (50.0 + eps, -2.0 + v));
+        check((50.0 - eps, -2.0 + v), (50.0, -2.0 + v));
+    }
+    // around the corner shared by the four tiles
+    for (dlat, dlng) in [(-eps, -eps), (-eps, eps), (eps, -eps), (eps, eps)] {
+        check((50.0 + dlat, -1.0 + dlng), (50.0, -1.0));
+    }
+}
+
+/// Validates the interpolation with both pixel conventions, against the bilinear resampling of
+/// GDAL from the geotransform of the tile.
+#[test]
+fn it_interpolates_with_the_pixel_convention() {
+    let dir = TileDir::new("pixel");
+    let plane = |lat: f64, lng: f64| {
+        let (x, y) = (
+            ((lng + 2.0) * 1200.0).round(),
+            ((50.0 - lat) * 1200.0).round(),
+        );
+        (x * 10.0 + y) as i16
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, plane);
+    // GDAL bilinear resampling at a geoposition, for a geotransform origin (lat, lng)
+    let gdal = |origin: (f64, f64), lat: f64, lng: f64| {
+        let (px, py) = (
+            (lng - origin.1) * 1200.0 - 0.5,
+            (origin.0 - lat) * 1200.0 - 0.5,
+        );
+        10.0 * px + py
+    };
+    let (lat, lng) = (49.5 - 0.25 / 1200.0, -1.5 + 0.4 / 1200.0);
+
+    // .hgt files: origin half a pixel north-west of the tile corner
+    let tiles = Tiles::new(&dir.path);
+    let h = tiles
+        .elevation_interpolated(lat as f32, lng as f32)
+        .unwrap() as f64;
+    let expect = gdal((50.0 + 0.5 / 1200.0, -2.0 - 0.5 / 1200.0), lat, lng);
+    assert!((expect - 6604.25).abs() < 1e-6);
+    assert!((h - expect).abs() < 0.1, "{} != {}", h, expect);
+
+    // origin at the tile corner
+    let tiles = Tiles::builder(&dir.path)
+        .pixel_convention(PixelConvention::Area)
+        .build();
+    let h = tiles
+        .elevation_interpolated(lat as f32, lng as f32)
+        .unwrap() as f64;
+    let expect = gdal((50.0, -2.0), lat, lng);
+    assert!((expect - 6598.75).abs() < 1e-6);
+    assert!((h - expect).abs() < 0.1, "{} != {}", h, expect);
+}
+
+/// Validates the inverse distance weighting of the void corners.
+#[test]
+fn it_interpolates_void_corners_from_their_neighborhood() {
+    let dir = TileDir::new("void_aware");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        let (row, col) = (
+            ((50.0 - lat) * 1200.0).round() as i32,
+            ((lng + 2.0) * 1200.0).round() as i32,
+        );
+        match (row, col) {
+            (600, 600) => VOID,
+            (600..=610, 700..=710) => VOID,
+            (_, c) if c > 600 => 200,
+            _ => 100,
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let cell = 1.0 / 1200.0;
+    let (lat, lng) = (49.5 - cell / 2.0, -1.5 + cell / 2.0);
+
+    assert_eq!(
+        tiles.elevation_interp(lat, -1.2, Interp::Nearest).unwrap(),
+        tiles.elevation(lat, -1.2).unwrap() as f32
+    );
+    assert_eq!(
+        tiles.elevation_interp(lat, lng, Interp::Bilinear).unwrap(),
+        tiles.elevation_interpolated(lat, lng).unwrap()
+    );
+    // the north-west corner is void: its western and central neighbors are 100, weighting 4
+    // (orthogonal neighbors weight 1, diagonal ones 1/2), the eastern ones 200, weighting 2
+    let void_aware = Interp::BilinearVoidAware { search: 1 };
+    let expect = 0.25 * (100.0 + 200.0 + 200.0) + 0.25 * (4.0 * 100.0 + 2.0 * 200.0) / 6.0;
+    let height = tiles.elevation_interp(lat, lng, void_aware).unwrap();
+    assert!((height - expect).abs() < 0.1, "{}", height);
+    // search 0 renormalizes over the valid corners
+    let height = tiles
+        .elevation_interp(lat, lng, Interp::BilinearVoidAware { search: 0 })
+        .unwrap();
+    assert!((height - 500.0 / 3.0).abs() < 0.1, "{}", height);
+
+    let (lat, lng) = (49.5 - 5.5 * cell, -2.0 + 705.5 * cell);
+    assert!(matches!(
+        tiles
+            .elevation_interp(lat, lng, void_aware)
+            .unwrap_err()
+            .downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert_eq!(
+        tiles
+            .elevation_interp(lat, lng, Interp::BilinearVoidAware { search: 6 })
+            .unwrap()
+            .round(),
+        200.0
+    );
+}
+
+/// Validates the resampling onto an arbitrary grid.
+#[test]
+fn it_resamples_regions_onto_arbitrary_grids() {
+    let dir = TileDir::new("sample_grid");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        ((lat - 49.0) * 1000.0 + (lng + 2.0) * 100.0).round() as i16
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    let raster = tiles
+        .sample_grid((49.9, -1.9), -0.0137, 0.0211, 20, 30, Interp::Bilinear)
+        .unwrap();
+    assert_eq!(raster.len(), 600);
+    for (index, &height) in raster.iter().enumerate() {
+        let (i, j) = (index / 30, index % 30);
+        let (lat, lng) = (49.9 - i as f64 * 0.0137, -1.9 + j as f64 * 0.0211);
+        let expect = (lat - 49.0) * 1000.0 + (lng + 2.0) * 100.0;
+        assert!((height as f64 - expect).abs() < 0.6, "row {} col {}", i, j);
+    }
+
+    let nearest = tiles
+        .sample_grid((49.5, -1.5), 0.1, 0.1, 2, 2, Interp::Nearest)
+        .unwrap();
+    assert_eq!(nearest[3], tiles.elevation(49.6, -1.4).unwrap() as f32);
+    assert!(tiles
+        .sample_grid((49.5, -1.5), 0.3, 0.3, 3, 3, Interp::Bilinear)
+        .is_err());
+}
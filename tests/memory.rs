@@ -0,0 +1,103 @@
+mod common;
+
+use common::{slope, write_tile, write_tile_with, TileDir, SRTM3_SIDE};
+use easy_srtm::{PixelConvention, SrtmError, Tiles};
+
+/// Validates that in-memory tiles return the same heights than the files.
+#[test]
+fn it_reads_elevation_from_tiles_in_memory() {
+    let dir = TileDir::new("memory");
+    for (lat0, lng0) in [(49, -2), (49, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    std::fs::write(dir.path.join("README.txt"), "not a tile").unwrap();
+    let tiles = Tiles::new(&dir.path);
+    let memory = Tiles::new(&dir.path).into_memory().unwrap();
+
+    assert_eq!(memory.bytes_loaded(), 2 * 1201 * 1201 * 2);
+    assert_eq!(memory.tile_names(), vec!["N49W001.hgt", "N49W002.hgt"]);
+
+    let positions = vec![(49.5, -1.5), (49.123, -1.0), (49.0302, -0.1916)];
+    positions.into_iter().for_each(|(lat, lng): (f32, f32)| {
+        let h = memory.elevation(lat, lng).unwrap();
+        assert!(tiles.elevation(lat, lng).unwrap() == h);
+        let h = memory.elevation_interpolated(lat, lng).unwrap();
+        assert!(tiles.elevation_interpolated(lat, lng).unwrap() == h);
+    });
+    assert!(memory.elevation(10.0, 10.0).is_err());
+}
+
+/// Validates that sidecar files next to the tiles are not loaded as tiles.
+#[test]
+fn it_ignores_sidecar_files() {
+    let dir = TileDir::new("sidecars");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let lowercase = write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    std::fs::rename(lowercase, dir.path.join("n49w001.HGT")).unwrap();
+    for sidecar in ["N49W002.hgt.aux.xml", "N49W002.prj", "N49W002.tfw"] {
+        std::fs::write(dir.path.join(sidecar), "sidecar").unwrap();
+    }
+    std::fs::create_dir(dir.path.join("N10E010.hgt")).unwrap();
+
+    let memory = Tiles::new(&dir.path).into_memory().unwrap();
+    assert_eq!(memory.tile_names(), vec!["N49W001.hgt", "N49W002.hgt"]);
+    assert_eq!(memory.elevation(49.5, -0.5).unwrap(), slope(49.5, -0.5));
+}
+
+/// Validates that tiles of configured dimensions, with fewer columns than rows, are loaded.
+#[test]
+fn it_reads_tiles_of_configured_dimensions_in_memory() {
+    let dir = TileDir::new("memory_dimensions");
+    write_tile_with(&dir.path, 65, 25, (601, SRTM3_SIDE), slope);
+    let tiles = Tiles::builder(&dir.path)
+        .dimensions(601, SRTM3_SIDE)
+        .build();
+    let memory = Tiles::builder(&dir.path)
+        .dimensions(601, SRTM3_SIDE)
+        .build()
+        .into_memory()
+        .unwrap();
+
+    assert_eq!(memory.bytes_loaded(), 601 * 1201 * 2);
+    for (lat, lng) in [(65.5, 25.5), (65.25, 25.1), (65.0, 25.0), (65.999, 25.999)] {
+        assert_eq!(
+            memory.elevation(lat, lng).unwrap(),
+            tiles.elevation(lat, lng).unwrap()
+        );
+        assert_eq!(
+            memory.elevation_interpolated(lat, lng).unwrap(),
+            tiles.elevation_interpolated(lat, lng).unwrap()
+        );
+    }
+    assert!(Tiles::new(&dir.path).into_memory().is_err());
+}
+
+/// Validates that in-memory tiles keep the configuration of the tiles they are loaded from.
+#[test]
+fn it_keeps_the_configuration_in_memory() {
+    let dir = TileDir::new("memory_config");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 0);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    let builder = || {
+        Tiles::builder(&dir.path)
+            .strict(true)
+            .pixel_convention(PixelConvention::Area)
+            .vertical_datum("EGM2008")
+    };
+    let tiles = builder().build();
+    let memory = builder().build().into_memory().unwrap();
+
+    assert_eq!(memory.tile_names(), vec!["N49W001.hgt"]);
+    let error = memory.elevation(49.5, -1.5).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::CorruptTile(_))
+    ));
+    assert_eq!(memory.vertical_datum(), "EGM2008");
+    for (lat, lng) in [(49.5, -0.5), (49.123, -0.987)] {
+        assert_eq!(
+            memory.elevation_interpolated(lat, lng).unwrap(),
+            tiles.elevation_interpolated(lat, lng).unwrap()
+        );
+    }
+}
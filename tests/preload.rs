@@ -0,0 +1,106 @@
+mod common;
+
+use common::{slope, write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::Tiles;
+use std::time::Instant;
+
+/// Validates that a track preload opens the present tiles and reports the missing ones.
+#[test]
+fn it_preloads_the_tiles_of_a_track() {
+    let dir = TileDir::new("preload");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+
+    let track = vec![
+        (49.1, -1.6),
+        (49.2, -1.4),
+        (49.3, -0.9),
+        (50.1, -0.9),
+        (50.2, -0.8),
+    ];
+    let missing = tiles.preload_track(&track).unwrap();
+    assert_eq!(missing, vec!["N50W001.hgt"]);
+
+    // preloaded tiles are read from the cache
+    std::fs::remove_file(dir.path.join("N49W002.hgt")).unwrap();
+    assert!(tiles.elevation(49.1, -1.6).is_ok());
+    assert!(Tiles::new(&dir.path).elevation(49.1, -1.6).is_err());
+}
+
+/// Validates the unchecked lookups from the preloaded tiles.
+#[test]
+fn it_reads_preloaded_tiles_unchecked() {
+    let dir = TileDir::new("unchecked");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    assert!(tiles.preload(&["N49W002.hgt"]).unwrap().is_empty());
+
+    for (lat, lng) in [(49.1, -1.6), (49.5, -1.5), (49.0, -2.0)] {
+        let h = tiles.elevation_cached_unchecked(lat, lng);
+        assert_eq!(h, tiles.elevation(lat, lng).unwrap());
+    }
+}
+
+/// Validates that unchecked lookups panic outside of the preloaded tiles.
+#[test]
+#[should_panic(expected = "tile N49W001.hgt is not opened")]
+fn it_panics_on_unchecked_lookups_of_tiles_not_opened() {
+    let dir = TileDir::new("unchecked-panic");
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    Tiles::new(&dir.path).elevation_cached_unchecked(49.5, -0.5);
+}
+
+/// Validates the cache statistics.
+#[test]
+fn it_reports_the_cache_statistics() {
+    let dir = TileDir::new("cache_stats");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let stats = tiles.cache_stats();
+    assert!(stats.tiles.is_empty());
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (0, 0, 0));
+
+    let start = Instant::now();
+    tiles.elevation(49.5, -1.5).unwrap();
+    tiles.elevation(49.5, -0.5).unwrap();
+    tiles.elevation(49.6, -1.5).unwrap();
+    assert!(tiles.elevation(10.0, 10.0).is_err());
+    let stats = tiles.cache_stats();
+    let mut names: Vec<&str> = stats.tiles.iter().map(|(name, _)| name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["N49W001.hgt", "N49W002.hgt"]);
+    assert!(stats.tiles.iter().all(|&(_, access)| access >= start));
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 3, 0));
+
+    tiles.elevation_cached_unchecked(49.5, -0.5);
+    let stats = tiles.cache_stats();
+    assert_eq!(stats.tiles[0].0, "N49W001.hgt");
+    assert_eq!(stats.hits, 2);
+
+    tiles.refresh().unwrap();
+    let stats = tiles.cache_stats();
+    assert!(stats.tiles.is_empty());
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 3, 2));
+}
+
+/// Validates that clones read the same tiles with their own cache.
+#[test]
+fn it_clones_tiles_with_an_empty_cache() {
+    let dir = TileDir::new("clone");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::builder(&dir.path)
+        .vertical_datum("WGS84 ellipsoidal")
+        .build();
+    tiles.elevation(49.5, -1.5).unwrap();
+
+    let clone = tiles.clone();
+    assert!(clone.cache_stats().tiles.is_empty());
+    assert_eq!(clone.vertical_datum(), "WGS84 ellipsoidal");
+    let thread = std::thread::spawn(move || (clone.elevation(49.5, -1.5).unwrap(), clone));
+    let (height, clone) = thread.join().unwrap();
+    assert_eq!(height, tiles.elevation(49.5, -1.5).unwrap());
+    assert_eq!(clone.cache_stats().tiles.len(), 1);
+    assert_eq!(tiles.cache_stats().hits, 1);
+}
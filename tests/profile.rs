@@ -0,0 +1,292 @@
+mod common;
+
+use common::{write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{haversine_m, SrtmError, Tiles, VOID};
+
+/// Validates the spacing of the profile samples.
+#[test]
+fn it_samples_profiles_at_a_fixed_spacing() {
+    let dir = TileDir::new("profile");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, _| (lat * 100.0) as i16);
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.1, -1.9), (49.2, -1.9));
+    let length = haversine_m(from, to);
+
+    let profile = tiles.profile_spaced(from, to, 1000.0).unwrap();
+    assert_eq!(profile.len(), 13);
+    assert_eq!((profile[0].lat, profile[0].lng), from);
+    assert_eq!((profile[12].lat, profile[12].lng), to);
+    assert_eq!(profile[12].distance_m, length);
+    for (i, point) in profile.iter().enumerate().take(12) {
+        assert!((point.distance_m - i as f32 * 1000.0).abs() < 0.1);
+        assert!((haversine_m(from, (point.lat, point.lng)) - point.distance_m).abs() < 1.0);
+        assert!((point.elevation - point.lat * 100.0).abs() < 1.0);
+    }
+
+    let profile = tiles.profile(from, to, 5).unwrap();
+    assert_eq!(profile.len(), 5);
+    assert!((profile[2].distance_m - length / 2.0).abs() < 0.1);
+    assert!(tiles.profile(from, to, 1).is_err());
+    assert!(tiles.profile_spaced(from, to, 0.0).is_err());
+}
+
+/// Validates the densification of adaptive profiles around a ridge.
+#[test]
+fn it_samples_adaptive_profiles_along_the_relief() {
+    let dir = TileDir::new("profile_adaptive");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if (lng + 1.5).abs() < 0.01 {
+            500
+        } else {
+            (lat * 100.0) as i16
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    let (from, to) = ((49.1, -1.9), (49.2, -1.9));
+    let profile = tiles.profile_adaptive(from, to, 2.0).unwrap();
+    assert_eq!(profile.len(), 2);
+    assert_eq!((profile[1].lat, profile[1].lng), to);
+
+    let (from, to) = ((49.5, -1.8), (49.5, -1.2));
+    let profile = tiles.profile_adaptive(from, to, 2.0).unwrap();
+    assert!(
+        profile.len() > 10 && profile.len() < 200,
+        "{}",
+        profile.len()
+    );
+    assert_eq!((profile[0].lat, profile[0].lng), from);
+    assert_eq!(profile[profile.len() - 1].distance_m, haversine_m(from, to));
+    assert!(profile
+        .windows(2)
+        .all(|p| p[0].distance_m < p[1].distance_m));
+    assert!(profile.iter().any(|point| point.elevation >= 499.0));
+    let ridge = profile
+        .iter()
+        .filter(|point| (point.lng + 1.5).abs() < 0.02);
+    assert!(ridge.count() * 2 > profile.len());
+    assert!(tiles.profile_adaptive(from, to, 0.0).is_err());
+}
+
+/// Validates the earth curvature drop applied to distant elevations.
+#[test]
+fn it_lowers_distant_elevations_by_the_curvature_drop() {
+    let dir = TileDir::new("curved");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 100);
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(
+        tiles.elevation_curved(49.5, -1.5, (49.5, -1.5)).unwrap(),
+        100.0
+    );
+    let distance = haversine_m((49.1, -1.5), (49.9, -1.5)) as f64;
+    let expect = 100.0 - distance * distance / (2.0 * 6_371_008.8);
+    let h = tiles.elevation_curved(49.9, -1.5, (49.1, -1.5)).unwrap();
+    assert!((h as f64 - expect).abs() < 0.1, "{} != {}", h, expect);
+    assert!((h + 521.0).abs() < 1.0);
+}
+
+/// Validates the line of sight over a ridge and the earth bulge.
+#[test]
+fn it_computes_the_visibility_between_sites() {
+    let dir = TileDir::new("line_of_sight");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, lng| {
+        if (lng + 1.5).abs() < 0.01 {
+            500
+        } else {
+            100
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let sites = [
+        (49.5, -1.8, 10.0),
+        (49.5, -1.7, 10.0),
+        (49.5, -1.2, 10.0),
+        (49.5, -1.3, 1000.0),
+    ];
+
+    assert!(tiles.has_line_of_sight(sites[0], sites[1]).unwrap());
+    assert!(!tiles.has_line_of_sight(sites[0], sites[2]).unwrap());
+    assert!(tiles.has_line_of_sight(sites[0], sites[3]).unwrap());
+    assert_eq!(
+        tiles.visibility_matrix(&sites).unwrap(),
+        vec![
+            vec![true, true, false, true],
+            vec![true, true, false, true],
+            vec![false, false, true, true],
+            vec![true, true, true, true],
+        ]
+    );
+    assert!(tiles
+        .has_line_of_sight(sites[0], (49.5, -0.5, 10.0))
+        .is_err());
+
+    let dir = TileDir::new("line_of_sight_bulge");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 0);
+    let tiles = Tiles::new(&dir.path);
+    assert!(tiles
+        .has_line_of_sight((49.5, -1.9, 2.0), (49.5, -1.8, 2.0))
+        .unwrap());
+    assert!(!tiles
+        .has_line_of_sight((49.5, -1.9, 2.0), (49.5, -1.1, 2.0))
+        .unwrap());
+}
+
+/// Validates the visibility between sites at the same position.
+#[test]
+fn it_sees_sites_at_the_same_position() {
+    let dir = TileDir::new("line_of_sight_same");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 100);
+    let tiles = Tiles::new(&dir.path);
+    let (mast, foot) = ((49.5, -1.5, 30.0), (49.5, -1.5, 0.0));
+
+    assert!(tiles.has_line_of_sight(mast, foot).unwrap());
+    assert_eq!(
+        tiles
+            .visibility_matrix(&[mast, foot, (49.6, -1.5, 30.0)])
+            .unwrap(),
+        vec![vec![true; 3]; 3]
+    );
+}
+
+/// Validates the horizon angles around an observer.
+#[test]
+fn it_computes_the_horizon_of_an_observer() {
+    let dir = TileDir::new("horizon");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, _| {
+        if lat > 49.55 {
+            1000
+        } else {
+            0
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    let horizon = tiles.horizon(49.5, -1.5, 2.0, 4).unwrap();
+    assert_eq!(horizon.len(), 4);
+    // the wall is about 5.6 km north
+    let wall = haversine_m((49.5, -1.5), (49.55, -1.5)) as f64;
+    let expect = (998.0 / wall).atan().to_degrees() as f32;
+    assert!((horizon[0] - expect).abs() < 0.3, "{:?}", horizon);
+    // the flat terrain is below the horizontal, by the curvature drop
+    for angle in &horizon[1..] {
+        assert!(*angle < 0.0 && *angle > -0.1, "{:?}", horizon);
+    }
+    let higher = tiles.horizon(49.5, -1.5, 500.0, 4).unwrap();
+    assert!(higher[0] < horizon[0] && higher[2] < horizon[2]);
+
+    assert!(tiles.horizon(49.5, -1.5, 2.0, 0).is_err());
+    assert!(tiles.horizon(49.9, -1.5, 2.0, 4).is_err());
+}
+
+/// Validates that voids along a sightline are not taken for terrain.
+#[test]
+fn it_reports_the_voids_along_a_sightline() {
+    let dir = TileDir::new("line_of_sight_voids");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, lng| {
+        if (lng + 1.6).abs() < 0.01 {
+            VOID
+        } else if (lng + 1.3).abs() < 0.01 {
+            500
+        } else {
+            100
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let tx = (49.5, -1.8, 300.0);
+
+    let error = tiles.has_line_of_sight(tx, (49.5, -1.5, 10.0)).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert!(!tiles.has_line_of_sight(tx, (49.5, -1.2, 10.0)).unwrap());
+    assert!(tiles.has_line_of_sight(tx, (49.5, -1.6, 10.0)).is_err());
+
+    let (_, _, clearances) = tiles
+        .coverage_map(tx, (49.5009, -1.5109), (49.4991, -1.4891), 868.0)
+        .unwrap();
+    assert!(clearances.iter().all(|c| c.is_nan()));
+    let (_, _, clearances) = tiles
+        .coverage_map(tx, (49.5009, -1.2109), (49.4991, -1.1891), 868.0)
+        .unwrap();
+    assert!(clearances.iter().all(|&c| c < 0.0));
+}
+
+/// Validates the Fresnel clearances of a region on both sides of a ridge.
+#[test]
+fn it_computes_the_coverage_of_a_transmitter() {
+    let dir = TileDir::new("coverage");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, lng| {
+        if (lng + 1.5).abs() < 0.01 {
+            500
+        } else {
+            100
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let tx = (49.5, -1.8, 30.0);
+
+    let (width, height, clearances) = tiles
+        .coverage_map(tx, (49.5009, -1.7109), (49.4991, -1.6891), 868.0)
+        .unwrap();
+    assert_eq!((width, height), (27, 3));
+    assert_eq!(clearances.len(), 81);
+    assert!(clearances.iter().all(|&c| c > 0.0 && c.is_finite()));
+
+    let (_, _, clearances) = tiles
+        .coverage_map(tx, (49.5009, -1.3109), (49.4991, -1.2891), 868.0)
+        .unwrap();
+    assert!(clearances.iter().all(|&c| c < 0.0));
+
+    let (_, _, clearances) = tiles
+        .coverage_map(tx, (49.5001, -1.8001), (49.4999, -1.7999), 868.0)
+        .unwrap();
+    assert_eq!(clearances, [f32::INFINITY]);
+    assert!(tiles
+        .coverage_map(tx, (49.6, -1.8), (49.5, -1.7), 0.0)
+        .is_err());
+}
+
+/// Validates the camera track over a ridge and a void patch.
+#[test]
+fn it_follows_the_terrain_with_a_capped_grade() {
+    let dir = TileDir::new("follow_terrain");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, lng| {
+        if (lng + 1.5).abs() < 0.01 {
+            300
+        } else if (lng + 1.3).abs() < 0.05 {
+            VOID
+        } else {
+            100
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let path = [(49.5, -1.8), (49.5, -1.5), (49.5, -1.2)];
+
+    let track = tiles.follow_terrain(&path, 50.0, 31).unwrap();
+    assert_eq!(track.len(), 61);
+    assert_eq!((track[0].0, track[0].1), path[0]);
+    assert_eq!((track[30].0, track[30].1), path[1]);
+    assert_eq!((track[60].0, track[60].1), path[2]);
+    assert!(track
+        .iter()
+        .all(|&(_, _, altitude)| altitude >= 150.0 - 0.01));
+    assert!(track[30].2 >= 350.0 - 0.01);
+    assert!((track[0].2 - 150.0).abs() < 0.01);
+    for pair in track.windows(2) {
+        let run = haversine_m((pair[0].0, pair[0].1), (pair[1].0, pair[1].1));
+        assert!((pair[1].2 - pair[0].2).abs() <= 0.2 * run + 0.1);
+    }
+    // over the voids, the camera holds its altitude
+    let voids = track
+        .iter()
+        .filter(|&&(_, lng, _)| (lng + 1.3).abs() < 0.04);
+    assert!(voids.clone().count() > 0);
+    assert!(voids
+        .into_iter()
+        .all(|&(_, _, altitude)| (altitude - 150.0).abs() < 0.01));
+
+    assert!(tiles.follow_terrain(&path[..1], 50.0, 31).is_err());
+    assert!(tiles.follow_terrain(&path, 50.0, 1).is_err());
+}
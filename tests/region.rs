@@ -0,0 +1,582 @@
+mod common;
+
+use common::{slope, write_tile, write_tile_with, TileDir, SRTM1_SIDE, SRTM3_SIDE};
+use easy_srtm::{decode_region, tiles_in_bbox, Kernel, Resolution, SrtmError, TileOrder, Tiles};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Validates the region retrieval across tile edges.
+#[test]
+fn it_fills_a_region_spanning_several_tiles() {
+    let dir = TileDir::new("region");
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    let tiles = Tiles::new(&dir.path);
+
+    let (from, to) = ((49.9, -1.05), (50.05, -0.9));
+    let mut out = vec![0; 300 * 200];
+    let (width, height) = tiles.fill_region(from, to, &mut out).unwrap();
+    assert_eq!((width, height), (181, 181));
+
+    let grid = tiles.grid((to.0, from.1), (from.0, to.1)).unwrap();
+    assert_eq!((grid.width, grid.height), (width, height));
+    assert_eq!(grid.data[..], out[..(width * height) as usize]);
+    assert_eq!(grid.data[0], slope(50.05, -1.05));
+    for row in 0..height {
+        for col in 0..width {
+            let (lat, lng) = grid.position(col, row);
+            let expect = tiles.elevation(lat, lng).unwrap();
+            let h = grid.get(col, row).unwrap();
+            assert!(expect == h, "Failed for col:{}, row:{})", col, row);
+        }
+    }
+
+    let mut small = vec![0; 181 * 180];
+    assert!(tiles.fill_region(from, to, &mut small).is_err());
+}
+
+/// Validates the split of a tile into overlapping blocks.
+#[test]
+fn it_splits_a_tile_into_windows() {
+    let dir = TileDir::new("windows");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let tile = tiles.load_tile(49.5, -1.5).unwrap();
+    assert_eq!((tile.width, tile.height), (1201, 1201));
+    assert_eq!(tile.north_west, (50.0, -2.0));
+
+    let blocks: Vec<_> = tiles.windows(49.5, -1.5, 401, 1).unwrap().collect();
+    assert_eq!(blocks.len(), 9);
+    let starts: Vec<_> = blocks.iter().map(|(c, r, _)| (*c, *r)).take(4).collect();
+    assert_eq!(starts, vec![(0, 0), (400, 0), (800, 0), (0, 400)]);
+    for (col0, row0, data) in blocks {
+        assert_eq!(data.len(), 401 * 401);
+        assert_eq!(data[0], tile.get(col0, row0).unwrap());
+        assert_eq!(
+            data[401 * 401 - 1],
+            tile.get(col0 + 400, row0 + 400).unwrap()
+        );
+    }
+
+    let blocks: Vec<_> = tiles.windows(49.5, -1.5, 500, 0).unwrap().collect();
+    assert_eq!(blocks.len(), 9);
+    assert_eq!(blocks[8].2.len(), 201 * 201);
+    assert!(tiles.windows(49.5, -1.5, 2, 2).is_err());
+}
+
+/// Validates the export of a tile as a standard file.
+#[test]
+fn it_writes_a_tile_back_to_a_file() {
+    let dir = TileDir::new("write_hgt");
+    let source = write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let out = TileDir::new("write_hgt_out");
+    let tiles = Tiles::new(&dir.path);
+
+    tiles.write_hgt(49.5, -1.5, &out.path).unwrap();
+    let written = out.path.join("N49W002.hgt");
+    assert_eq!(
+        std::fs::read(&written).unwrap(),
+        std::fs::read(&source).unwrap()
+    );
+    assert_eq!(
+        Resolution::try_from(written.as_path()).unwrap(),
+        Resolution::SRTM3
+    );
+    assert!(tiles.write_hgt(10.5, 10.5, &out.path).is_err());
+
+    // over the tile being read
+    let expect = std::fs::read(&source).unwrap();
+    tiles.write_hgt(49.5, -1.5, &dir.path).unwrap();
+    assert_eq!(std::fs::read(&source).unwrap(), expect);
+    assert!(!dir.path.join("N49W002.hgt.partial").exists());
+
+    // tiles of other dimensions
+    write_tile_with(&dir.path, 65, 25, (601, SRTM3_SIDE), slope);
+    let tiles = Tiles::builder(&dir.path)
+        .dimensions(601, SRTM3_SIDE)
+        .build();
+    let error = tiles.write_hgt(65.5, 25.5, &out.path).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::InvalidArgument(_))
+    ));
+    assert!(!out.path.join("N65E025.hgt").exists());
+}
+
+/// Validates that the tiles listed for a bounding box are the ones needed by its grid.
+#[test]
+fn it_lists_the_tiles_needed_by_a_grid() {
+    let dir = TileDir::new("tiles_for_bbox");
+    let (from, to) = ((49.5, -1.5), (50.0, -1.0));
+    let tiles = Tiles::new(&dir.path);
+    let names = tiles.tiles_for_bbox(from, to);
+    assert_eq!(names.len(), 4);
+    assert!(names.iter().all(|name| !tiles.has_tile(name)));
+    assert_eq!(tiles.missing_in_bbox(from, to), names);
+
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    assert!(names.iter().all(|name| tiles.has_tile(name)));
+    assert!(tiles.missing_in_bbox(from, to).is_empty());
+    assert!(tiles.grid(from, to).is_ok());
+
+    std::fs::remove_file(dir.path.join("N50W001.hgt")).unwrap();
+    assert!(Tiles::new(&dir.path).grid(from, to).is_err());
+    assert_eq!(tiles.missing_in_bbox(from, to), vec!["N50W001.hgt"]);
+}
+
+/// Validates the tiles and the grid of a bounding box crossing the antimeridian.
+#[test]
+fn it_reads_a_bounding_box_across_the_antimeridian() {
+    let dir = TileDir::new("bbox_antimeridian");
+    write_tile(&dir.path, 45, 179, SRTM3_SIDE, |lat, lng| {
+        (lat * 100.0 + lng) as i16
+    });
+    write_tile(&dir.path, 45, -180, SRTM3_SIDE, |lat, lng| {
+        (lat * 100.0 - lng) as i16
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((45.6, 179.9), (45.5, -179.9));
+    assert_eq!(
+        tiles.tiles_for_bbox(from, to),
+        ["N45E179.hgt", "N45W180.hgt"]
+    );
+    assert!(tiles.missing_in_bbox(from, to).is_empty());
+
+    let grid = tiles.grid(from, to).unwrap();
+    assert_eq!((grid.width, grid.height), (241, 121));
+    for row in 0..grid.height {
+        for col in 0..grid.width {
+            let (lat, lng) = grid.position(col, row);
+            assert_eq!(
+                grid.get(col, row).unwrap(),
+                tiles.elevation(lat, lng).unwrap()
+            );
+        }
+    }
+}
+
+/// Validates the geographic enumeration of the tiles of a box crossing the antimeridian.
+#[test]
+fn it_enumerates_the_tiles_across_the_antimeridian() {
+    let expect = [
+        "S16E178.hgt",
+        "S16E179.hgt",
+        "S16W180.hgt",
+        "S17E178.hgt",
+        "S17E179.hgt",
+        "S17W180.hgt",
+    ];
+    assert_eq!(tiles_in_bbox((-15.5, 178.5), (-16.5, -179.5)), expect);
+    assert_eq!(tiles_in_bbox((-16.5, 178.5), (-15.5, -179.5)), expect);
+    let tiles = Tiles::new("the_folder_path");
+    assert_eq!(
+        tiles.tiles_for_bbox((-15.5, 178.5), (-16.5, -179.5)),
+        expect
+    );
+}
+
+/// Validates that the ordered tiles of a bounding box are a permutation of its tiles.
+#[test]
+fn it_orders_the_tiles_of_a_bounding_box() {
+    let tiles = Tiles::new("the_folder_path");
+    let (from, to) = ((46.5, -2.5), (48.5, 1.5));
+    let names = tiles.tiles_for_bbox(from, to);
+    assert_eq!(names.len(), 15);
+    assert_eq!(
+        tiles.tiles_for_bbox_ordered(from, to, TileOrder::RowMajor),
+        names
+    );
+
+    let north_west = ["N48W003.hgt", "N48W002.hgt", "N47W003.hgt", "N47W002.hgt"];
+    for order in [TileOrder::Morton, TileOrder::Hilbert] {
+        let mut ordered = tiles.tiles_for_bbox_ordered(from, to, order);
+        let mut square = ordered[..4].to_vec();
+        square.sort();
+        let mut expect = north_west.to_vec();
+        expect.sort();
+        assert_eq!(square, expect, "{:?}", order);
+        ordered.sort();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(ordered, sorted, "{:?}", order);
+    }
+}
+
+/// Validates the size of the tiles covering a bounding box.
+#[test]
+fn it_sums_the_bytes_of_the_tiles_of_a_region() {
+    let dir = TileDir::new("region_bytes");
+    write_tile(&dir.path, 49, -2, SRTM1_SIDE, slope);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    std::fs::write(dir.path.join("N50W002.hgt"), [0; 10]).unwrap();
+    let tiles = Tiles::new(&dir.path);
+
+    let srtm1 = Resolution::SRTM1.file_size();
+    assert_eq!(
+        tiles.region_bytes((49.5, -1.5), (49.4, -1.4)).unwrap(),
+        srtm1
+    );
+    assert_eq!(
+        tiles.region_bytes((49.5, -1.5), (49.4, -0.5)).unwrap(),
+        srtm1 + Resolution::SRTM3.file_size()
+    );
+    assert_eq!(tiles.region_bytes((10.5, 10.5), (10.4, 10.6)).unwrap(), 0);
+    assert!(tiles.region_bytes((50.5, -1.5), (49.4, -1.4)).is_err());
+}
+
+/// Validates that the read-ahead buffer does not change the region samples.
+#[test]
+fn it_reads_regions_through_a_read_ahead_buffer() {
+    let dir = TileDir::new("read_buffer");
+    for (lat0, lng0) in [(49, -2), (49, -1), (50, -2), (50, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    let unbuffered = Tiles::new(&dir.path);
+    for (from, to) in [((50.0, -2.0), (49.0, -1.0)), ((49.7, -1.3), (49.2, -0.6))] {
+        let expect = unbuffered.grid(from, to).unwrap();
+        for size in [1, 100, 2402, 10_000, 1 << 20] {
+            let tiles = Tiles::builder(&dir.path).read_buffer(size).build();
+            assert_eq!(tiles.grid(from, to).unwrap(), expect, "size {}", size);
+        }
+    }
+}
+
+/// Validates the delta encoding of a region and its decoding.
+#[test]
+fn it_encodes_and_decodes_regions() {
+    let dir = TileDir::new("encode_region");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lat > 49.45 && lng > -1.45 {
+            easy_srtm::VOID
+        } else {
+            (((lat * 40.0).sin() + (lng * 30.0).cos()) * 2000.0) as i16
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.5, -1.5), (49.3, -1.3));
+    let grid = tiles.grid(from, to).unwrap();
+
+    let bytes = tiles.encode_region(from, to).unwrap();
+    assert_eq!(&bytes[..5], b"SRD1\x03");
+    assert!(bytes.len() < grid.data.len() * 2);
+    assert_eq!(decode_region(&bytes).unwrap(), grid);
+
+    let tiles = Tiles::builder(&dir.path).void_value(0).build();
+    let decoded = decode_region(&tiles.encode_region(from, to).unwrap()).unwrap();
+    for (sample, expect) in decoded.data.iter().zip(&grid.data) {
+        assert_eq!(
+            *sample,
+            if *expect == 0 {
+                easy_srtm::VOID
+            } else {
+                *expect
+            }
+        );
+    }
+
+    assert!(decode_region(&bytes[..bytes.len() - 1]).is_err());
+    assert!(decode_region(&[bytes.as_slice(), &[1]].concat()).is_err());
+    assert!(decode_region(&bytes[1..]).is_err());
+    // the highest height, then the greatest increase
+    let overflow = [0xff, 0xff, 0x03, 0xff, 0xff, 0xff, 0xff, 0x0f];
+    let error = decode_region(&[&bytes[..21], &overflow].concat()).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::InvalidArgument(_))
+    ));
+}
+
+/// Validates the row streaming and the run-length encoding of a region.
+#[test]
+fn it_streams_and_encodes_region_rows() {
+    let dir = TileDir::new("rows");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, _| {
+        (lat * 10.0).floor() as i16
+    });
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, |lat, _| {
+        (lat * 10.0).floor() as i16
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.5, -1.5), (49.2, -0.5));
+
+    let grid = tiles.grid(from, to).unwrap();
+    let rows = tiles.rows(from, to).unwrap();
+    assert_eq!((rows.width(), rows.height()), (grid.width, grid.height));
+    assert_eq!(rows.north_west(), grid.north_west);
+    let streamed: Vec<i16> = rows.flat_map(|row| row.unwrap()).collect();
+    assert_eq!(streamed, grid.data);
+
+    let runs = tiles.grid_rle(from, to).unwrap();
+    assert_eq!(runs.len(), 4);
+    assert_eq!(easy_srtm::expand_rle(&runs), grid.data);
+}
+
+/// Validates the triangle strips streamed from a region.
+#[test]
+fn it_streams_mesh_strips() {
+    let dir = TileDir::new("mesh");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.5, -1.5), (49.49, -1.49));
+
+    let grid = tiles.grid(from, to).unwrap();
+    let strips: Vec<_> = tiles
+        .mesh_strips(from, to)
+        .unwrap()
+        .map(|strip| strip.unwrap())
+        .collect();
+    assert_eq!(strips.len() as u32, grid.height - 1);
+    for (row, strip) in strips.iter().enumerate() {
+        assert_eq!(strip.row, row as u32);
+        assert_eq!(strip.vertices.len() as u32, 2 * grid.width);
+        for (i, [lng, lat, height]) in strip.vertices.iter().enumerate() {
+            let (col, row) = (i as u32 / 2, row as u32 + i as u32 % 2);
+            assert_eq!(grid.position(col, row), (*lat, *lng));
+            assert_eq!(grid.get(col, row), Some(*height as i16));
+        }
+    }
+}
+
+/// Validates the classification of a region into elevation bands.
+#[test]
+fn it_classifies_regions_into_bands() {
+    let dir = TileDir::new("bands");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lng < -1.8 {
+            easy_srtm::VOID
+        } else {
+            ((lat - 49.0) * 1000.0).round() as i16
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.9, -1.9), (49.1, -1.1));
+
+    let grid = tiles.grid(from, to).unwrap();
+    let (width, height, bands) = tiles.band_grid(from, to, &[200, 500, 500, 800]).unwrap();
+    assert_eq!((width, height), (grid.width, grid.height));
+    for (band, sample) in bands.iter().zip(&grid.data) {
+        let expect = match *sample {
+            easy_srtm::VOID => easy_srtm::VOID_BAND,
+            h if h < 200 => 0,
+            h if h < 500 => 1,
+            h if h < 800 => 3,
+            _ => 4,
+        };
+        assert_eq!(*band, expect, "failed for {}", sample);
+    }
+    assert!(bands.contains(&easy_srtm::VOID_BAND) && bands.contains(&0) && bands.contains(&4));
+
+    assert!(tiles.band_grid(from, to, &[]).is_err());
+    assert!(tiles.band_grid(from, to, &[500, 200]).is_err());
+}
+
+/// Validates that missing tiles are read as voids when allowed.
+#[test]
+fn it_reads_missing_tiles_as_voids_when_allowed() {
+    let dir = TileDir::new("allow_missing");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let (from, to) = ((49.5, -1.5), (49.4, -0.5));
+    assert!(Tiles::new(&dir.path).grid(from, to).is_err());
+
+    let tiles = Tiles::builder(&dir.path).allow_missing(true).build();
+    let grid = tiles.grid(from, to).unwrap();
+    for col in 0..grid.width {
+        let (lat, lng) = grid.position(col, 0);
+        let expect = if lng < -1.0 {
+            tiles.elevation(lat, lng).unwrap()
+        } else {
+            easy_srtm::VOID
+        };
+        assert_eq!(grid.get(col, 0), Some(expect), "failed for lng {}", lng);
+    }
+    assert_eq!(
+        grid.get(grid.width - 1, grid.height - 1),
+        Some(easy_srtm::VOID)
+    );
+    assert!(tiles.elevation(49.5, -0.5).is_err());
+}
+
+/// Validates that a set cancel flag aborts the region read.
+#[test]
+fn it_cancels_region_reads() {
+    let dir = TileDir::new("cancel");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.9, -1.9), (49.1, -1.1));
+
+    let cancel = AtomicBool::new(false);
+    let grid = tiles.grid_cancellable(from, to, &cancel).unwrap();
+    assert_eq!(grid, tiles.grid(from, to).unwrap());
+
+    cancel.store(true, Ordering::Relaxed);
+    let error = tiles.grid_cancellable(from, to, &cancel).unwrap_err();
+    assert!(matches!(error.downcast_ref(), Some(SrtmError::Cancelled)));
+}
+
+/// Validates the void-aware smoothing of region grids.
+#[test]
+fn it_smooths_region_grids() {
+    let dir = TileDir::new("smoothed");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        let (row, col) = (
+            ((50.0 - lat) * 1200.0).round() as i32,
+            ((lng + 2.0) * 1200.0).round() as i32,
+        );
+        match (row, col) {
+            (600, 600) => 1000,
+            (600, 610) | (601, 610) => easy_srtm::VOID,
+            (600..=602, 620..=622) => easy_srtm::VOID,
+            _ => 100,
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.5 + 2.0 / 1200.0, -1.5 - 2.0 / 1200.0), (49.49, -1.48));
+    let raw = tiles.grid(from, to).unwrap();
+    // the spike is at (2, 2), the voids at (12, 2), (12, 3) and from (22, 2) to (24, 4)
+    assert_eq!(raw.get(2, 2), Some(1000));
+
+    let mean = tiles.grid_smoothed(from, to, Kernel::Mean(3)).unwrap();
+    assert_eq!((mean.width, mean.height), (raw.width, raw.height));
+    assert_eq!(mean.get(2, 2), Some(200));
+    assert_eq!(mean.get(0, 0), Some(100));
+    assert_eq!(mean.get(12, 2), Some(100));
+    assert_eq!(mean.get(23, 3), Some(easy_srtm::VOID));
+    assert_eq!(mean.get(23, 2), Some(100));
+    assert_eq!(mean.get(23, 0), Some(100));
+
+    let median = tiles.grid_smoothed(from, to, Kernel::Median(5)).unwrap();
+    assert_eq!(median.get(2, 2), Some(100));
+    let gaussian = tiles.grid_smoothed(from, to, Kernel::Gaussian(3)).unwrap();
+    let center = gaussian.get(2, 2).unwrap();
+    assert!(center > 200 && center < 1000, "{}", center);
+    assert_eq!(gaussian.get(5, 5), Some(100));
+
+    assert!(tiles.grid_smoothed(from, to, Kernel::Mean(4)).is_err());
+    assert!(tiles.grid_smoothed(from, to, Kernel::Median(1)).is_err());
+}
+
+/// Validates the visit of the region samples, and its interruption.
+#[test]
+fn it_visits_the_samples_of_a_region() {
+    let dir = TileDir::new("for_each_in_bbox");
+    for (lat0, lng0) in [(49, -2), (49, -1)] {
+        write_tile(&dir.path, lat0, lng0, SRTM3_SIDE, slope);
+    }
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.5, -1.01), (49.49, -0.99));
+    let grid = tiles.grid(from, to).unwrap();
+
+    let mut visited = Vec::new();
+    tiles
+        .for_each_in_bbox(from, to, |lat, lng, height| {
+            visited.push((lat, lng, height));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(visited.len(), grid.data.len());
+    for (i, &(lat, lng, height)) in visited.iter().enumerate() {
+        let (col, row) = (i as u32 % grid.width, i as u32 / grid.width);
+        assert_eq!((lat, lng), grid.position(col, row));
+        assert_eq!(Some(height), grid.get(col, row));
+    }
+
+    let mut count = 0;
+    tiles
+        .for_each_in_bbox(from, to, |_, _, _| {
+            count += 1;
+            if count == 5 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .unwrap();
+    assert_eq!(count, 5);
+    assert!(tiles
+        .for_each_in_bbox((49.5, -0.01), (49.49, 0.01), |_, _, _| {
+            ControlFlow::Continue(())
+        })
+        .is_err());
+}
+
+/// Validates the difference of a region between two datasets.
+#[test]
+fn it_computes_the_difference_between_two_datasets() {
+    let (old, new) = (TileDir::new("diff_old"), TileDir::new("diff_new"));
+    write_tile(&old.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lng < -1.9004 {
+            easy_srtm::VOID
+        } else {
+            slope(lat, lng)
+        }
+    });
+    write_tile(&new.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lat > 49.9004 {
+            easy_srtm::VOID
+        } else {
+            slope(lat, lng) + 10
+        }
+    });
+    let (from, to) = ((49.95, -1.95), (49.5, -1.5));
+    let (old_tiles, new_tiles) = (Tiles::new(&old.path), Tiles::new(&new.path));
+
+    let diff = new_tiles.diff(&old_tiles, from, to).unwrap();
+    let grid = new_tiles.grid(from, to).unwrap();
+    assert_eq!((diff.width, diff.height), (grid.width, grid.height));
+    for row in 0..diff.height {
+        for col in 0..diff.width {
+            let (lat, lng) = diff.position(col, row);
+            let expect = if lat > 49.9004 || lng < -1.9004 {
+                easy_srtm::VOID
+            } else {
+                10
+            };
+            assert_eq!(diff.get(col, row), Some(expect), "({}, {})", lat, lng);
+        }
+    }
+
+    let srtm1 = TileDir::new("diff_srtm1");
+    write_tile(&srtm1.path, 49, -2, SRTM1_SIDE, slope);
+    let error = new_tiles
+        .diff(&Tiles::new(&srtm1.path), from, to)
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::MixedResolution)
+    ));
+    assert!(new_tiles
+        .diff(&old_tiles, (50.5, -1.5), (50.2, -1.2))
+        .is_err());
+}
+
+/// Validates that the sparse region holds the samples of the grid which are not voids.
+#[test]
+fn it_reads_the_region_without_voids() {
+    let dir = TileDir::new("region_sparse");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lng < -1.6004 {
+            easy_srtm::VOID
+        } else {
+            slope(lat, lng)
+        }
+    });
+    let (from, to) = ((49.7, -1.7), (49.6, -1.5));
+    let tiles = Tiles::new(&dir.path);
+
+    let sparse = tiles.region_sparse(from, to).unwrap();
+    let grid = tiles.grid(from, to).unwrap();
+    let valid = grid.data.iter().filter(|&&h| h != easy_srtm::VOID).count();
+    assert!(valid > 0 && valid < grid.data.len());
+    assert_eq!(sparse.len(), valid);
+    for row in 0..grid.height {
+        for col in 0..grid.width {
+            let height = grid.get(col, row).unwrap();
+            let expect = Some(height).filter(|&h| h != easy_srtm::VOID);
+            assert_eq!(sparse.get(&(col, row)).copied(), expect);
+        }
+    }
+    assert!(tiles.region_sparse((50.5, -1.5), (50.2, -1.2)).is_err());
+}
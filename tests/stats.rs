@@ -0,0 +1,134 @@
+mod common;
+
+use common::{write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{SrtmError, Tiles, VOID};
+
+/// Validates the mean elevation of the samples inside a polygon.
+#[test]
+fn it_computes_the_mean_elevation_of_a_polygon() {
+    let dir = TileDir::new("polygon");
+    // 100 m west of -1.5, 200 m east of it, voids north of 49.8
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| match (lat, lng) {
+        (lat, _) if lat > 49.8 => VOID,
+        (_, lng) if lng < -1.5 => 100,
+        _ => 200,
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    // a triangle over the western half
+    let west = [(49.2, -1.9), (49.6, -1.9), (49.4, -1.6)];
+    assert_eq!(tiles.polygon_mean(&west).unwrap(), 100.0);
+
+    // a square centered on -1.5: half of the samples on each side
+    let square = [(49.2, -1.6), (49.4, -1.6), (49.4, -1.4), (49.2, -1.4)];
+    let mean = tiles.polygon_mean(&square).unwrap();
+    assert!((mean - 150.0).abs() < 1.0, "{}", mean);
+
+    // voids are excluded
+    let north = [(49.7, -1.9), (49.9, -1.9), (49.9, -1.6), (49.7, -1.6)];
+    assert_eq!(tiles.polygon_mean(&north).unwrap(), 100.0);
+    let void = [(49.85, -1.9), (49.95, -1.9), (49.95, -1.6)];
+    assert!(matches!(
+        tiles.polygon_mean(&void).unwrap_err().downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert!(tiles.polygon_mean(&west[..2]).is_err());
+}
+
+/// Validates the highest and lowest samples of a region, voids excluded.
+#[test]
+fn it_finds_the_highest_and_lowest_samples() {
+    let dir = TileDir::new("highest");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        let (x, y) = (
+            ((lng + 2.0) * 1200.0).round() as u32,
+            ((50.0 - lat) * 1200.0).round() as u32,
+        );
+        match (x, y) {
+            (600, 600) | (610, 600) => 900,
+            (605, 590) => -5,
+            _ if y > 1000 => VOID,
+            _ => 100,
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+    let (from, to) = ((49.6, -1.6), (49.0, -1.4));
+    let position = |(x, y): (u32, u32)| (50.0 - y as f32 / 1200.0, -2.0 + x as f32 / 1200.0);
+
+    let (lat, lng, height) = tiles.highest(from, to).unwrap();
+    assert_eq!(height, 900);
+    let expect = position((600, 600));
+    assert!((lat - expect.0).abs() < 1e-4 && (lng - expect.1).abs() < 1e-4);
+    let (lat, lng, height) = tiles.lowest(from, to).unwrap();
+    assert_eq!(height, -5);
+    let expect = position((605, 590));
+    assert!((lat - expect.0).abs() < 1e-4 && (lng - expect.1).abs() < 1e-4);
+    assert_eq!(tiles.elevation(lat, lng).unwrap(), -5);
+
+    let error = tiles.highest((49.1, -1.6), (49.0, -1.5)).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+}
+
+/// Validates the rank of an elevation among the samples of its tile.
+#[test]
+fn it_ranks_an_elevation_within_its_tile() {
+    let dir = TileDir::new("percentile_rank");
+    // a quarter of voids in the north, heights growing southward in the rest
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, _| {
+        if lat > 49.75 {
+            VOID
+        } else {
+            ((49.75 - lat) * 1000.0) as i16
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    assert_eq!(tiles.percentile_rank(49.75, -1.5).unwrap(), 0.0);
+    let rank = tiles.percentile_rank(49.1, -1.5).unwrap();
+    assert!((rank - 0.866).abs() < 0.01, "{}", rank);
+    assert!(tiles.percentile_rank(49.0, -1.5).unwrap() > 0.99);
+    let misses = tiles.cache_stats().misses;
+    tiles.percentile_rank(49.375, -1.5).unwrap();
+    assert_eq!(tiles.cache_stats().misses, misses);
+
+    let err = tiles.percentile_rank(49.9, -1.5).unwrap_err();
+    assert!(matches!(err.downcast_ref(), Some(SrtmError::NoData { .. })));
+}
+
+/// Validates the statistics of the samples along a buffered centerline.
+#[test]
+fn it_computes_the_statistics_of_a_corridor() {
+    let dir = TileDir::new("corridor_stats");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |lat, lng| {
+        if lat > 49.7 {
+            VOID
+        } else {
+            ((lng + 2.0) * 1000.0) as i16
+        }
+    });
+    let tiles = Tiles::new(&dir.path);
+
+    // 1000 m is about 0.0139 degree of longitude at 49.5
+    let stats = tiles
+        .corridor_stats(&[(49.2, -1.5), (49.8, -1.5)], 1000.0)
+        .unwrap();
+    assert!((485..=487).contains(&stats.min), "{:?}", stats);
+    assert!((512..=514).contains(&stats.max), "{:?}", stats);
+    assert!((stats.mean - 500.0).abs() < 1.0, "{:?}", stats);
+    // about 600 rows of 33 samples, and no voids
+    assert!((18_000..22_000).contains(&stats.count), "{:?}", stats);
+
+    let disc = tiles.corridor_stats(&[(49.5, -1.5)], 1000.0).unwrap();
+    // about π km² of cells of 93 × 60 m
+    assert!((500..650).contains(&disc.count), "{:?}", disc);
+
+    let err = tiles
+        .corridor_stats(&[(49.8, -1.5), (49.9, -1.5)], 100.0)
+        .unwrap_err();
+    assert!(matches!(err.downcast_ref(), Some(SrtmError::NoData { .. })));
+    assert!(tiles.corridor_stats(&[], 100.0).is_err());
+    assert!(tiles.corridor_stats(&[(49.5, -1.5)], -1.0).is_err());
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{slope, write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{SrtmError, Tiles, VOID};
+
+/// Validates the rejection of tiles made only of zeros or voids in strict mode.
+#[test]
+fn it_rejects_empty_tiles_in_strict_mode() {
+    let dir = TileDir::new("strict");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 0);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, |lat, _| {
+        if lat < 49.5 {
+            0
+        } else {
+            VOID
+        }
+    });
+    write_tile(&dir.path, 50, -2, SRTM3_SIDE, slope);
+
+    let tiles = Tiles::builder(&dir.path).strict(true).build();
+    for _ in 0..2 {
+        let error = tiles.elevation(49.5, -1.5).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(SrtmError::CorruptTile(_))
+        ));
+    }
+    assert!(tiles.elevation(49.5, -0.5).is_err());
+    assert!(tiles.elevation(50.5, -1.5).is_ok());
+
+    let tiles = Tiles::new(&dir.path);
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), 0);
+}
+
+/// Validates that refreshing the tiles reads the tiles replaced since they were opened.
+#[test]
+fn it_reads_replaced_tiles_after_a_refresh() {
+    let dir = TileDir::new("refresh");
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, |_, _| 0);
+    let tiles = Tiles::builder(&dir.path).strict(true).build();
+    assert!(tiles.elevation(49.5, -1.5).is_err());
+
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, slope);
+    write_tile(&dir.path, 49, -1, SRTM3_SIDE, slope);
+    assert!(tiles.elevation(49.5, -1.5).is_err());
+    assert_eq!(tiles.elevation(49.5, -0.5).unwrap(), slope(49.5, -0.5));
+
+    tiles.refresh().unwrap();
+    assert_eq!(tiles.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+
+    assert!(Tiles::new(dir.path.join("missing")).refresh().is_err());
+}
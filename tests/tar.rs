@@ -0,0 +1,115 @@
+#![cfg(feature = "tar")]
+mod common;
+
+use common::{slope, write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{Resolution, Tiles};
+use std::path::Path;
+
+/// Appends a tar entry to the archive.
+fn append(archive: &mut Vec<u8>, name: &str, typeflag: u8, data: &[u8]) {
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = typeflag;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(data);
+    archive.resize(archive.len().div_ceil(512) * 512, 0);
+}
+
+/// Writes a tar archive of the tiles N49W002 and N49W001, and of other files.
+fn write_archive(dir: &Path) -> std::path::PathBuf {
+    let tiles = TileDir::new("tar-tiles");
+    let first = std::fs::read(write_tile(&tiles.path, 49, -2, SRTM3_SIDE, slope)).unwrap();
+    let second = std::fs::read(write_tile(&tiles.path, 49, -1, SRTM3_SIDE, slope)).unwrap();
+
+    let mut archive = Vec::new();
+    append(&mut archive, "srtm/", b'5', &[]);
+    append(
+        &mut archive,
+        "srtm/N49W002.hgt.aux.xml",
+        b'0',
+        b"<PAMDataset/>",
+    );
+    append(&mut archive, "srtm/N49W002.hgt", b'0', &first);
+    let long = format!("srtm/{}/N49W001.hgt", "nested".repeat(20));
+    append(&mut archive, "././@LongLink", b'L', long.as_bytes());
+    append(&mut archive, &long[..100], b'0', &second);
+    archive.extend_from_slice(&[0; 1024]);
+
+    let path = dir.join("srtm.tar");
+    std::fs::write(&path, archive).unwrap();
+    path
+}
+
+/// Validates the elevation retrieval from tiles stored in a tar archive.
+#[test]
+fn it_reads_elevation_from_a_tar_archive() {
+    let dir = TileDir::new("tar");
+    let archive = write_archive(&dir.path);
+    let tiles = Tiles::from_tar(&archive).unwrap();
+
+    assert!(tiles.has_tile("N49W002.hgt") && tiles.has_tile("N49W001.hgt"));
+    assert!(!tiles.has_tile("N49W002.hgt.aux.xml"));
+    let positions = vec![
+        (49.5, -1.5),
+        (49.123, -1.987),
+        (49.0302, -0.1916),
+        (49.0, -1.0),
+    ];
+    positions.into_iter().for_each(|(lat, lng): (f32, f32)| {
+        let h = tiles.elevation(lat, lng).unwrap();
+        assert_eq!(
+            h,
+            slope(lat as f64, lng as f64),
+            "failed for ({}, {})",
+            lat,
+            lng
+        );
+    });
+    let grid = tiles.grid((49.5, -1.1), (49.4, -0.9)).unwrap();
+    assert_eq!(grid.get(0, 0), Some(tiles.elevation(49.5, -1.1).unwrap()));
+    assert!(tiles.elevation(10.0, 10.0).is_err());
+
+    std::fs::write(&archive, [1; 2048]).unwrap();
+    assert!(Tiles::from_tar(&archive).is_err());
+}
+
+/// Validates that the archive entries are copies of the tiles for the lookups of every
+/// resolution.
+#[test]
+fn it_lists_the_archived_copies_of_a_tile() {
+    let dir = TileDir::new("tar_copies");
+    let archive = write_archive(&dir.path);
+    let tiles = Tiles::from_tar(&archive).unwrap();
+    let height = slope(49.5, -1.5);
+
+    assert_eq!(
+        tiles.elevation_all_resolutions(49.5, -1.5).unwrap(),
+        vec![(Resolution::SRTM3, height)]
+    );
+    assert_eq!(
+        tiles.elevation_best(49.5, -1.5).unwrap(),
+        (Resolution::SRTM3, height)
+    );
+    assert!(tiles.elevation_all_resolutions(10.0, 10.0).is_err());
+}
+
+/// Validates that the archived tiles are loaded in memory.
+#[test]
+fn it_loads_the_archived_tiles_in_memory() {
+    let dir = TileDir::new("tar_memory");
+    let archive = write_archive(&dir.path);
+    let memory = Tiles::from_tar(&archive).unwrap().into_memory().unwrap();
+
+    assert_eq!(memory.tile_names(), vec!["N49W001.hgt", "N49W002.hgt"]);
+    assert_eq!(memory.bytes_loaded(), 2 * 1201 * 1201 * 2);
+    assert_eq!(memory.elevation(49.5, -1.5).unwrap(), slope(49.5, -1.5));
+    assert_eq!(memory.elevation(49.5, -0.5).unwrap(), slope(49.5, -0.5));
+}
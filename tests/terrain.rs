@@ -0,0 +1,176 @@
+mod common;
+
+use common::{write_tile, TileDir, SRTM3_SIDE};
+use easy_srtm::{Resolution, SrtmError, Tiles, VOID};
+
+/// Validates the slope, aspect and normal of a planar terrain, across tile edges.
+#[test]
+fn it_computes_the_terrain_shape() {
+    let dir = TileDir::new("terrain");
+    // rising by 10 m per sample northward and 5 m per sample westward
+    let plane = |lat: f64, lng: f64| {
+        let (x, y) = (
+            ((lng + 2.0) * 1200.0).round(),
+            ((50.0 - lat) * 1200.0).round(),
+        );
+        (20000.0 - 10.0 * y - 5.0 * x) as i16
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, plane);
+    write_tile(&dir.path, 48, -2, SRTM3_SIDE, plane);
+    let tiles = Tiles::new(&dir.path);
+
+    for (lat, lng) in [(49.5, -1.5), (49.0, -1.5)] {
+        let terrain = tiles.terrain(lat, lng).unwrap();
+        assert_eq!(terrain.height, tiles.elevation(lat, lng).unwrap() as f32);
+
+        let (dx, dy) = Resolution::SRTM3.cell_size_m(lat);
+        let (east, north) = (-5.0 / dx, 10.0 / dy);
+        let slope = east.hypot(north).atan().to_degrees();
+        assert!((terrain.slope_deg - slope).abs() < 1e-3, "{:?}", terrain);
+        // facing south-east, downhill
+        let aspect = (-east).atan2(-north).to_degrees().rem_euclid(360.0);
+        assert!((terrain.aspect_deg - aspect).abs() < 1e-3, "{:?}", terrain);
+        assert!(terrain.aspect_deg > 90.0 && terrain.aspect_deg < 180.0);
+
+        let [e, n, up] = terrain.normal;
+        assert!(((e * e + n * n + up * up).sqrt() - 1.0).abs() < 1e-6);
+        assert!(e > 0.0 && n < 0.0 && up > 0.0);
+    }
+
+    let flat = TileDir::new("terrain-flat");
+    write_tile(&flat.path, 49, -2, SRTM3_SIDE, |lat, _| {
+        if lat > 49.9 {
+            VOID
+        } else {
+            7
+        }
+    });
+    let tiles = Tiles::new(&flat.path);
+    let terrain = tiles.terrain(49.5, -1.5).unwrap();
+    assert_eq!((terrain.slope_deg, terrain.aspect_deg), (0.0, 0.0));
+    assert_eq!(terrain.normal, [0.0, 0.0, 1.0]);
+    assert!(matches!(
+        tiles.terrain(49.95, -1.5).unwrap_err().downcast_ref(),
+        Some(SrtmError::NoData { .. })
+    ));
+    assert!(tiles.terrain(49.0, -1.5).is_err());
+}
+
+/// Validates the steepest descent and ascent paths, across tile edges.
+#[test]
+fn it_follows_the_steepest_path() {
+    let dir = TileDir::new("steepest");
+    // a bowl centered on (49.0, -1.5), on the edge of N48W002 and N49W002
+    let bowl = |lat: f64, lng: f64| {
+        let (x, y) = ((lng + 1.5) * 1200.0, (lat - 49.0) * 1200.0);
+        (x * x + y * y).sqrt().round().min(30000.0) as i16
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, bowl);
+    write_tile(&dir.path, 48, -2, SRTM3_SIDE, bowl);
+    let tiles = Tiles::new(&dir.path);
+
+    let path = tiles.steepest_path((49.01, -1.49), true, 100).unwrap();
+    let &(lat, lng, height) = path.last().unwrap();
+    assert_eq!((lat, lng, height), (49.0, -1.5, 0));
+    assert!(path.len() <= 25);
+    assert!(path.windows(2).all(|pair| pair[1].2 < pair[0].2));
+
+    let path = tiles.steepest_path((49.01, -1.49), false, 5).unwrap();
+    assert_eq!(path.len(), 6);
+    assert!(path.windows(2).all(|pair| pair[1].2 > pair[0].2));
+
+    let path = tiles.steepest_path((49.0, -1.5), true, 100).unwrap();
+    assert_eq!(path, vec![(49.0, -1.5, 0)]);
+}
+
+/// Validates the buildable samples of a region against the terrain slopes, across tile edges.
+#[test]
+fn it_computes_the_buildable_mask() {
+    let dir = TileDir::new("buildable");
+    // steeper and steeper away from the meridian -2, with a void
+    let bowl = |lat: f64, lng: f64| {
+        let (x, y) = (
+            ((lng + 2.0) * 1200.0).round(),
+            ((50.0 - lat) * 1200.0).round(),
+        );
+        if (x, y) == (250.0, 588.0) {
+            VOID
+        } else {
+            (0.02 * x * x) as i16
+        }
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, bowl);
+    write_tile(&dir.path, 49, -3, SRTM3_SIDE, bowl);
+    let tiles = Tiles::new(&dir.path);
+
+    let (from, to) = ((49.52, -2.02), (49.5, -1.75));
+    let (width, height, mask) = tiles.buildable_mask(from, to, 8.0).unwrap();
+    let grid = tiles.grid(from, to).unwrap();
+    assert_eq!((width, height), (grid.width, grid.height));
+    assert_eq!(mask.len(), grid.data.len());
+    for row in 0..height {
+        for col in 0..width {
+            let (lat, lng) = grid.position(col, row);
+            let buildable = mask[(col + row * width) as usize];
+            match tiles.terrain(lat, lng) {
+                Ok(terrain) => assert_eq!(
+                    buildable,
+                    terrain.slope_deg <= 8.0,
+                    "({}, {}): {:?}",
+                    lat,
+                    lng,
+                    terrain
+                ),
+                Err(_) => assert!(!buildable, "({}, {})", lat, lng),
+            }
+        }
+    }
+    assert!(mask.contains(&true) && mask.contains(&false));
+    assert!(tiles
+        .buildable_mask((49.52, -1.02), (49.5, -1.0), 8.0)
+        .is_err());
+}
+
+/// Validates the pits of a region, across tile edges, next to voids and on plateaus.
+#[test]
+fn it_finds_the_pits_of_a_region() {
+    let dir = TileDir::new("find_pits");
+    let pits = |lat: f64, lng: f64| {
+        let (x, y) = (
+            ((lng + 3.0) * 1200.0).round() as i32,
+            ((50.0 - lat) * 1200.0).round() as i32,
+        );
+        match (x, y) {
+            // a pit on the tile edge, and one inside the region
+            (1200, 600) | (1210, 610) => 10,
+            // a pit next to a void
+            (1220, 600) => 10,
+            (1221, 600) => VOID,
+            // a flat sink of two samples
+            (1230, 600) | (1231, 600) => 10,
+            _ => 100,
+        }
+    };
+    write_tile(&dir.path, 49, -2, SRTM3_SIDE, pits);
+    write_tile(&dir.path, 49, -3, SRTM3_SIDE, pits);
+    let tiles = Tiles::new(&dir.path);
+
+    let found = tiles.find_pits((49.51, -2.0), (49.49, -1.97)).unwrap();
+    let on_grid = |(lat, lng, height): (f32, f32, i16)| {
+        (
+            ((lng as f64 + 3.0) * 1200.0).round() as i32,
+            ((50.0 - lat as f64) * 1200.0).round() as i32,
+            height,
+        )
+    };
+    let found: Vec<_> = found.into_iter().map(on_grid).collect();
+    assert_eq!(found, vec![(1200, 600, 10), (1210, 610, 10)]);
+
+    // the western neighbors of the edge sample are read from the western tile
+    let found = tiles.find_pits((49.5, -2.0), (49.5, -2.0)).unwrap();
+    assert_eq!(
+        found.into_iter().map(on_grid).collect::<Vec<_>>(),
+        vec![(1200, 600, 10)]
+    );
+    assert!(tiles.find_pits((49.5, -1.001), (49.5, -1.0)).is_err());
+}